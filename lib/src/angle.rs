@@ -0,0 +1,84 @@
+use std::f64;
+use std::fmt;
+use std::ops;
+use coordinate::Scalar;
+
+/// An angle in radians. `new`, `From` and the arithmetic operators store the value
+/// as given, without wrapping it; `normalize` maps it into `(-π, π]`, so that an
+/// angle difference does not drive a torsion spring the long way around the ±π seam.
+#[derive(Copy, Clone, Debug)]
+pub struct Rad<T: Scalar = f64>(pub T);
+
+impl<T: Scalar> Rad<T> {
+    pub fn new(value: T) -> Self {
+        Rad(value)
+    }
+
+    pub fn value(&self) -> T {
+        self.0
+    }
+
+    /// Map any value into `(-π, π]`.
+    pub fn normalize(&self) -> Self {
+        let two_pi = T::from_f64(2. * f64::consts::PI);
+        let wrapped = self.0 - two_pi * (self.0 / two_pi).round();
+        // round() takes half-way cases away from zero, so +π lands on -π
+        if wrapped <= -T::from_f64(f64::consts::PI) {
+            Rad(wrapped + two_pi)
+        } else {
+            Rad(wrapped)
+        }
+    }
+}
+
+impl<T: Scalar> From<T> for Rad<T> {
+    fn from(value: T) -> Self {
+        Rad(value)
+    }
+}
+
+impl<T: Scalar> ops::Add for Rad<T> {
+    type Output = Rad<T>;
+    fn add(self, rhs: Self) -> Self {
+        Rad(self.0 + rhs.0)
+    }
+}
+
+impl<T: Scalar> ops::Sub for Rad<T> {
+    type Output = Rad<T>;
+    fn sub(self, rhs: Self) -> Self {
+        Rad(self.0 - rhs.0)
+    }
+}
+
+impl<T: Scalar> ops::Neg for Rad<T> {
+    type Output = Rad<T>;
+    fn neg(self) -> Self {
+        Rad(-self.0)
+    }
+}
+
+impl<T: Scalar + fmt::Display> fmt::Display for Rad<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} rad", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_wraps_into_range() {
+        let over = Rad::new(1.5 * f64::consts::PI);
+        let wrapped = over.normalize();
+        assert!(wrapped.value() > -f64::consts::PI && wrapped.value() <= f64::consts::PI);
+        assert!((wrapped.value() - (-0.5 * f64::consts::PI)).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn test_normalize_is_noop_within_range() {
+        let within = Rad::new(0.25 * f64::consts::PI);
+        assert!((within.normalize().value() - within.value()).abs() < 1.0e-10);
+    }
+}
@@ -0,0 +1,32 @@
+/// Coefficients for a somite's soft-body links: an inner spring-damper holding each
+/// pair of adjacent somites at its rest length, and a goal spring-damper pulling a
+/// somite toward a target resting position, so driving the goal position over time
+/// can express actuation (e.g. peristalsis) without applying a force directly.
+pub struct BodyConfig {
+    pub inner_spring_k: f64,
+    pub inner_spring_c: f64,
+    pub goal_k: f64,
+    pub goal_friction: f64,
+}
+
+impl BodyConfig {
+    pub fn new(inner_spring_k: f64, inner_spring_c: f64, goal_k: f64, goal_friction: f64) -> Self {
+        BodyConfig {
+            inner_spring_k: inner_spring_k,
+            inner_spring_c: inner_spring_c,
+            goal_k: goal_k,
+            goal_friction: goal_friction,
+        }
+    }
+}
+
+impl Default for BodyConfig {
+    fn default() -> Self {
+        BodyConfig {
+            inner_spring_k: 0.,
+            inner_spring_c: 0.,
+            goal_k: 0.,
+            goal_friction: 0.,
+        }
+    }
+}
@@ -14,6 +14,12 @@ pub struct Config {
     pub sp_natural_length: f64,
     pub sp_k: f64,
     pub dp_c: f64,
+    // goal spring-damper coefficients (Somite::set_goal_position /
+    // Dynamics::calculate_goal_force) pulling a somite toward a driven target
+    // position instead of only holding rest_length with its neighbors; 0 (the
+    // default) leaves goal springs inert even if a goal position is set
+    pub goal_spring_k: f64,
+    pub goal_spring_c: f64,
     pub horizon_ts_k0: f64,
     pub horizon_ts_k1: f64,
     pub vertical_ts_k0: f64,
@@ -25,6 +31,10 @@ pub struct Config {
     pub static_friction_coeff: f64,
     pub dynamic_friction_coeff: f64,
     pub viscosity_friction_coeff: f64,
+    // drag coefficient opposing a somite's full 3-axis velocity, modeling ambient
+    // medium resistance (air, water, ...) rather than ground friction; 0 (the
+    // default) disables it
+    pub medium_friction_coeff: f64,
     pub tip_sub_static_friction_coeff: f64,
     pub tip_sub_dynamic_friction_coeff: f64,
     pub tip_sub_viscosity_friction_coeff: f64,
@@ -32,6 +42,16 @@ pub struct Config {
     pub gripping_phase_threshold: f64,
     pub gripping_shear_stress_k: f64,
     pub gripping_shear_stress_c: f64,
+    // ground contact stiffness/damping for the compliant penalty contact model
+    // (F_n = contact_k*d - contact_c*v_z while v_z < 0); 0 (the default) means no
+    // compliant model is configured, so ground contact falls back to the original
+    // hard force/velocity clamp
+    pub contact_k: f64,
+    pub contact_c: f64,
+    // coefficient applied to the separation velocity when the compliant contact
+    // model pushes a somite back out of the ground; 0 (the default) absorbs all
+    // energy on separation, matching the original clamp's behavior
+    pub contact_restitution: f64,
 }
 
 impl Config {
@@ -51,6 +71,8 @@ impl Config {
             "sp_natural_length" => self.sp_natural_length = val,
             "sp_k" => self.sp_k = val,
             "dp_c" => self.dp_c = val,
+            "goal_spring_k" => self.goal_spring_k = val,
+            "goal_spring_c" => self.goal_spring_c = val,
             "horizon_ts_k0" => self.horizon_ts_k0 = val,
             "horizon_ts_k1" => self.horizon_ts_k1 = val,
             "vertical_ts_k0" => self.vertical_ts_k0 = val,
@@ -64,6 +86,7 @@ impl Config {
             "static_friction_coeff" => self.static_friction_coeff = val,
             "dynamic_friction_coeff" => self.dynamic_friction_coeff = val,
             "viscosity_friction_coeff" => self.viscosity_friction_coeff = val,
+            "medium_friction_coeff" => self.medium_friction_coeff = val,
             "tip_sub_static_friction_coeff" => self.tip_sub_static_friction_coeff = val,
             "tip_sub_dynamic_friction_coeff" => self.tip_sub_dynamic_friction_coeff = val,
             "tip_sub_viscosity_friction_coeff" => self.tip_sub_viscosity_friction_coeff = val,
@@ -71,6 +94,9 @@ impl Config {
             "gripping_phase_threshold" => self.gripping_phase_threshold = val,
             "gripping_shear_stress_k" => self.gripping_shear_stress_k = val,
             "gripping_shear_stress_c" => self.gripping_shear_stress_c = val,
+            "contact_k" => self.contact_k = val,
+            "contact_c" => self.contact_c = val,
+            "contact_restitution" => self.contact_restitution = val,
             _ => panic!("invalid config: {}", key),
         };
     }
@@ -90,6 +116,8 @@ impl default::Default for Config {
             sp_natural_length: 0.1,                                // m
             sp_k: 100.,                                            // N/m
             dp_c: 10.,                                             // Ns/m
+            goal_spring_k: 0.,                                     // N/m
+            goal_spring_c: 0.,                                     // Ns/m
             horizon_ts_k0: 0.,                                     // N/rad
             horizon_ts_k1: 0.,                                     // N/rad
             vertical_ts_k0: 0.,                                    // N/rad
@@ -101,6 +129,7 @@ impl default::Default for Config {
             static_friction_coeff: 10.,                            //
             dynamic_friction_coeff: 7.,                            //
             viscosity_friction_coeff: 5.,                          // Ns/m
+            medium_friction_coeff: 0.,                             // Ns/m
             tip_sub_static_friction_coeff: 1.,                     //
             tip_sub_dynamic_friction_coeff: 0.7,                   //
             tip_sub_viscosity_friction_coeff: 0.5,                 // Ns/m
@@ -108,6 +137,9 @@ impl default::Default for Config {
             gripping_phase_threshold: f64::consts::PI * 5.0 / 4.0, //
             gripping_shear_stress_k: 500.,
             gripping_shear_stress_c: 10.,
+            contact_k: 0.,
+            contact_c: 0.,
+            contact_restitution: 0.,
         }
     }
 }
@@ -122,6 +154,7 @@ impl fmt::Display for Config {
              static friction coefficient: {} Ns/m\n\
              dynamic friction coefficient: {} Ns/m\n\
              viscosity friction coefficient: {} \n\
+             medium friction coefficient: {} Ns/m\n\
              tip sub static friction coefficient: {} Ns/m\n\
              tip sub dynamic friction coefficient: {} Ns/m\n\
              tip sub viscosity friction coefficient: {} \n\
@@ -137,6 +170,9 @@ impl fmt::Display for Config {
              natural_length: {} m\n\
              [dumper]\n\
              c: {} Ns/m\n\
+             [goal spring]\n\
+             k: {} N/m\n\
+             c: {} Ns/m\n\
              [torsion spring]\n\
              horizon k0: {} N/rad\n\
              horizon k1: {} N/rad\n\
@@ -149,6 +185,10 @@ impl fmt::Display for Config {
              [gripping]
              gripping shear stress k: {} N/m\n\
              gripping shear stress c: {} Ns/m\n\
+             [ground contact]\n\
+             k: {} N/m\n\
+             c: {} Ns/m\n\
+             restitution: {}\n\
              [simulation]\n\
              one time step: {} s",
             self.somite_mass,
@@ -156,6 +196,7 @@ impl fmt::Display for Config {
             self.static_friction_coeff,
             self.dynamic_friction_coeff,
             self.viscosity_friction_coeff,
+            self.medium_friction_coeff,
             self.tip_sub_static_friction_coeff,
             self.tip_sub_dynamic_friction_coeff,
             self.tip_sub_viscosity_friction_coeff,
@@ -168,6 +209,8 @@ impl fmt::Display for Config {
             self.sp_k,
             self.sp_natural_length,
             self.dp_c,
+            self.goal_spring_k,
+            self.goal_spring_c,
             self.horizon_ts_k0,
             self.horizon_ts_k1,
             self.vertical_ts_k0,
@@ -178,6 +221,9 @@ impl fmt::Display for Config {
             self.realtime_tunable_ts_rom_max,
             self.gripping_shear_stress_k,
             self.gripping_shear_stress_c,
+            self.contact_k,
+            self.contact_c,
+            self.contact_restitution,
             self.time_delta,
         )
     }
@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+use coordinate::Coordinate;
+use somite::Somite;
+
+/// A single somite-somite overlap found by the broad phase: the indices of the two
+/// somites, how deep they've penetrated, and the unit normal pointing from `i` to `j`.
+pub struct Contact {
+    pub i: usize,
+    pub j: usize,
+    pub penetration: f64,
+    pub normal: Coordinate,
+}
+
+type Cell = (i64, i64, i64);
+
+/// Every grid cell a sphere of `radius` centered at `center` overlaps, found by
+/// walking the integer row/column/layer range spanned by `center ± radius`.
+fn cells_of(center: Coordinate, radius: f64, cell_size: f64) -> Vec<Cell> {
+    let lo = center - Coordinate::new(radius, radius, radius);
+    let hi = center + Coordinate::new(radius, radius, radius);
+    let (lx, ly, lz) = (
+        (lo.x / cell_size).floor() as i64,
+        (lo.y / cell_size).floor() as i64,
+        (lo.z / cell_size).floor() as i64,
+    );
+    let (hx, hy, hz) = (
+        (hi.x / cell_size).floor() as i64,
+        (hi.y / cell_size).floor() as i64,
+        (hi.z / cell_size).floor() as i64,
+    );
+
+    let mut cells = Vec::new();
+    for cx in lx..=hx {
+        for cy in ly..=hy {
+            for cz in lz..=hz {
+                cells.push((cx, cy, cz));
+            }
+        }
+    }
+    cells
+}
+
+/// Find all overlapping somite pairs via a uniform spatial hash, skipping directly
+/// connected neighbors (i, i+1): the structural springs already keep those at the
+/// right distance, so flagging them as collisions would fight the spring forces.
+pub fn find_contacts(somites: &[Somite]) -> Vec<Contact> {
+    let max_radius = somites.iter().fold(0.0_f64, |acc, s| acc.max(s.radius));
+    if max_radius <= 0. {
+        return Vec::new();
+    }
+    let cell_size = 2. * max_radius;
+
+    let mut grid: HashMap<Cell, Vec<usize>> = HashMap::new();
+    for (i, s) in somites.iter().enumerate() {
+        for cell in cells_of(s.get_position(), s.radius, cell_size) {
+            grid.entry(cell).or_insert_with(Vec::new).push(i);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut contacts = Vec::new();
+    for indices in grid.values() {
+        for &i in indices {
+            for &j in indices {
+                if i >= j || i + 1 == j {
+                    continue;
+                }
+                if !seen.insert((i, j)) {
+                    continue;
+                }
+
+                let p_i = somites[i].get_position();
+                let p_j = somites[j].get_position();
+                let distance = (p_j - p_i).norm();
+                let penetration = somites[i].radius + somites[j].radius - distance;
+                if penetration > 0. && distance > 0. {
+                    contacts.push(Contact {
+                        i: i,
+                        j: j,
+                        penetration: penetration,
+                        normal: (p_j - p_i) / distance,
+                    });
+                }
+            }
+        }
+    }
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_contacts_ignores_chain_neighbors() {
+        let somites = vec![
+            Somite::new_still_somite(1., 1., Coordinate::new(0., 0., 1.)),
+            Somite::new_still_somite(1., 1., Coordinate::new(1., 0., 1.)),
+        ];
+        // somites 0 and 1 overlap, but they are direct chain neighbors
+        assert!(find_contacts(&somites).is_empty());
+    }
+
+    #[test]
+    fn test_find_contacts_detects_fold_back() {
+        let somites = vec![
+            Somite::new_still_somite(1., 1., Coordinate::new(0., 0., 1.)),
+            Somite::new_still_somite(1., 1., Coordinate::new(3., 0., 1.)),
+            Somite::new_still_somite(1., 1., Coordinate::new(0.5, 0., 1.)),
+        ];
+        // somite 2 folds back onto somite 0, even though they aren't chain neighbors
+        let contacts = find_contacts(&somites);
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].i, 0);
+        assert_eq!(contacts[0].j, 2);
+        assert!((contacts[0].penetration - 1.5).abs() < 1.0e-10);
+    }
+}
@@ -0,0 +1,33 @@
+/// Ground-contact model used when resolving normal/tangential ground reaction each step.
+#[derive(Clone, Copy)]
+pub enum ContactModel {
+    /// Penalty-style spring-damper friction via `Dynamics::calculate_friction` (the
+    /// long-standing default); chatters and can tunnel through the terrain at large
+    /// `time_delta`.
+    Penalty,
+    /// Each somite-ground contact solved as a unilateral constraint
+    /// `0 <= gap ⊥ lambda_n >= 0` by projected Gauss-Seidel, with the tangential
+    /// impulse clamped to the Coulomb friction cone. See
+    /// `Dynamics::resolve_complementarity_contacts`.
+    Complementarity { restitution: f64, max_iterations: usize, tolerance: f64 },
+}
+
+impl ContactModel {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "penalty" => Ok(ContactModel::Penalty),
+            "complementarity" => Ok(ContactModel::Complementarity {
+                restitution: 0.,
+                max_iterations: 50,
+                tolerance: 1.0e-6,
+            }),
+            _ => Err(format!("unknown contact model: {}", name)),
+        }
+    }
+}
+
+impl Default for ContactModel {
+    fn default() -> Self {
+        ContactModel::Penalty
+    }
+}
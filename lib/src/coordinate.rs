@@ -1,27 +1,120 @@
+use std::f64;
 use std::fmt;
 use std::ops;
+use ops as float_ops;
+
+/// Minimal numeric interface `Coordinate<T>` needs: the arithmetic operators, a
+/// square root, and the additive/multiplicative identities. Implemented for `f64`
+/// (and `f32`) so the simulator can run in reduced precision, and is small enough
+/// that an automatic-differentiation scalar type can implement it too.
+pub trait Scalar:
+    Copy
+    + ops::Add<Output = Self>
+    + ops::Sub<Output = Self>
+    + ops::Mul<Output = Self>
+    + ops::Div<Output = Self>
+    + ops::Neg<Output = Self>
+    + PartialEq
+    + PartialOrd
+{
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn acos(self) -> Self;
+    fn signum(self) -> Self;
+    fn round(self) -> Self;
+    fn zero() -> Self;
+    fn one() -> Self;
+    // lets generic code materialize literal tolerances/constants (e.g. an EPSILON)
+    // without requiring a full `From<f64>` impl
+    fn from_f64(v: f64) -> Self;
+}
+
+impl Scalar for f64 {
+    fn sqrt(self) -> Self {
+        float_ops::sqrt(self)
+    }
+    fn abs(self) -> Self {
+        if self < 0. {
+            -self
+        } else {
+            self
+        }
+    }
+    fn acos(self) -> Self {
+        float_ops::acos(self)
+    }
+    fn signum(self) -> Self {
+        float_ops::signum(self)
+    }
+    fn round(self) -> Self {
+        float_ops::round(self)
+    }
+    fn zero() -> Self {
+        0.
+    }
+    fn one() -> Self {
+        1.
+    }
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+}
+
+impl Scalar for f32 {
+    fn sqrt(self) -> Self {
+        self.sqrt()
+    }
+    fn abs(self) -> Self {
+        if self < 0. {
+            -self
+        } else {
+            self
+        }
+    }
+    fn acos(self) -> Self {
+        self.acos()
+    }
+    fn signum(self) -> Self {
+        self.signum()
+    }
+    fn round(self) -> Self {
+        self.round()
+    }
+    fn zero() -> Self {
+        0.
+    }
+    fn one() -> Self {
+        1.
+    }
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
 
 #[derive(Copy, Clone, Debug)]
-pub struct Coordinate {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+pub struct Coordinate<T: Scalar = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
 }
 
-impl Coordinate {
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
+/// Default, `f64`-scalar coordinate; the type every existing call site keeps using.
+pub type Coord = Coordinate<f64>;
+
+impl<T: Scalar> Coordinate<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
         Coordinate { x: x, y: y, z: z }
     }
 
     pub fn zero() -> Self {
-        Self::new(0., 0., 0.)
+        Self::new(T::zero(), T::zero(), T::zero())
     }
 
-    pub fn to_tuple(&self) -> (f64, f64, f64) {
+    pub fn to_tuple(&self) -> (T, T, T) {
         (self.x, self.y, self.z)
     }
 
-    pub fn from_tuple(t: (f64, f64, f64)) -> Self {
+    pub fn from_tuple(t: (T, T, T)) -> Self {
         Coordinate {
             x: t.0,
             y: t.1,
@@ -29,11 +122,11 @@ impl Coordinate {
         }
     }
 
-    pub fn norm(&self) -> f64 {
-        (self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
+    pub fn norm(&self) -> T {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
 
-    pub fn inner_product(&self, rhs: Self) -> f64 {
+    pub fn inner_product(&self, rhs: Self) -> T {
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
     }
 
@@ -46,8 +139,8 @@ impl Coordinate {
     }
 }
 
-impl ops::Add for Coordinate {
-    type Output = Coordinate;
+impl<T: Scalar> ops::Add for Coordinate<T> {
+    type Output = Coordinate<T>;
     fn add(self, rhs: Self) -> Self {
         Coordinate {
             x: self.x + rhs.x,
@@ -57,7 +150,7 @@ impl ops::Add for Coordinate {
     }
 }
 
-impl ops::AddAssign for Coordinate {
+impl<T: Scalar> ops::AddAssign for Coordinate<T> {
     fn add_assign(&mut self, rhs: Self) {
         *self = Coordinate {
             x: self.x + rhs.x,
@@ -67,8 +160,8 @@ impl ops::AddAssign for Coordinate {
     }
 }
 
-impl ops::Sub for Coordinate {
-    type Output = Coordinate;
+impl<T: Scalar> ops::Sub for Coordinate<T> {
+    type Output = Coordinate<T>;
     fn sub(self, rhs: Self) -> Self {
         Coordinate {
             x: self.x - rhs.x,
@@ -78,7 +171,7 @@ impl ops::Sub for Coordinate {
     }
 }
 
-impl ops::SubAssign for Coordinate {
+impl<T: Scalar> ops::SubAssign for Coordinate<T> {
     fn sub_assign(&mut self, rhs: Self) {
         *self = Coordinate {
             x: self.x - rhs.x,
@@ -88,9 +181,9 @@ impl ops::SubAssign for Coordinate {
     }
 }
 
-impl ops::Mul<f64> for Coordinate {
-    type Output = Coordinate;
-    fn mul(self, rhs: f64) -> Self {
+impl<T: Scalar> ops::Mul<T> for Coordinate<T> {
+    type Output = Coordinate<T>;
+    fn mul(self, rhs: T) -> Self {
         Coordinate {
             x: self.x * rhs,
             y: self.y * rhs,
@@ -99,9 +192,9 @@ impl ops::Mul<f64> for Coordinate {
     }
 }
 
-impl ops::Div<f64> for Coordinate {
-    type Output = Coordinate;
-    fn div(self, rhs: f64) -> Self {
+impl<T: Scalar> ops::Div<T> for Coordinate<T> {
+    type Output = Coordinate<T>;
+    fn div(self, rhs: T) -> Self {
         Coordinate {
             x: self.x / rhs,
             y: self.y / rhs,
@@ -110,18 +203,197 @@ impl ops::Div<f64> for Coordinate {
     }
 }
 
-impl PartialEq for Coordinate {
+impl<T: Scalar> PartialEq for Coordinate<T> {
     fn eq(&self, rhs: &Self) -> bool {
         self.x == rhs.x && self.y == rhs.y && self.z == rhs.z
     }
 }
 
-impl fmt::Display for Coordinate {
+impl<T: Scalar + fmt::Display> fmt::Display for Coordinate<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "(x:{}, y:{}, z:{})", self.x, self.y, self.z)
     }
 }
 
+/// A unit quaternion representing an orientation, so somites can carry a persistent
+/// attitude instead of having geometry re-derived from point triples every step.
+#[derive(Copy, Clone, Debug)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Quaternion {
+            w: w,
+            x: x,
+            y: y,
+            z: z,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Quaternion::new(1., 0., 0., 0.)
+    }
+
+    pub fn from_axis_angle(axis: Coordinate, angle: f64) -> Self {
+        let half = angle / 2.;
+        let n = axis / axis.norm();
+        Quaternion::new(half.cos(), n.x * half.sin(), n.y * half.sin(), n.z * half.sin())
+    }
+
+    pub fn norm(&self) -> f64 {
+        (self.w.powi(2) + self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let n = self.norm();
+        Quaternion::new(self.w / n, self.x / n, self.y / n, self.z / n)
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn to_tuple(&self) -> (f64, f64, f64, f64) {
+        (self.w, self.x, self.y, self.z)
+    }
+
+    pub fn dot(&self, rhs: Self) -> f64 {
+        self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    /// Rotate `v` by this quaternion: promote `v` to a pure quaternion and compute
+    /// `q * v * q.conjugate()`.
+    pub fn rotate(&self, v: Coordinate) -> Coordinate {
+        let pure_v = Quaternion::new(0., v.x, v.y, v.z);
+        let rotated = *self * pure_v * self.conjugate();
+        Coordinate::new(rotated.x, rotated.y, rotated.z)
+    }
+
+    /// Build the quaternion that rotates the world frame onto the orthonormal body
+    /// frame whose x-axis is `forward` and whose (approximate) z-axis is `up` — `up`
+    /// is re-orthogonalized against `forward` rather than trusted directly, so it only
+    /// needs to be roughly "up", not exactly perpendicular to `forward`. Used by
+    /// `body_orientation` to turn the tail-to-head axis and the world vertical into an
+    /// attitude quaternion. Converts the resulting rotation matrix to a quaternion via
+    /// the standard largest-diagonal-term method for numerical stability near +/-180
+    /// degree rotations.
+    pub fn from_basis(forward: Coordinate, up: Coordinate) -> Self {
+        let x_axis = forward / forward.norm();
+        let y_axis = {
+            let y = up.cross_product(x_axis);
+            y / y.norm()
+        };
+        let z_axis = x_axis.cross_product(y_axis);
+
+        let (m00, m10, m20) = (x_axis.x, x_axis.y, x_axis.z);
+        let (m01, m11, m21) = (y_axis.x, y_axis.y, y_axis.z);
+        let (m02, m12, m22) = (z_axis.x, z_axis.y, z_axis.z);
+        let trace = m00 + m11 + m22;
+
+        if trace > 0. {
+            let s = 0.5 / (trace + 1.).sqrt();
+            Quaternion::new(0.25 / s, (m21 - m12) * s, (m02 - m20) * s, (m10 - m01) * s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = 2. * (1. + m00 - m11 - m22).sqrt();
+            Quaternion::new((m21 - m12) / s, 0.25 * s, (m01 + m10) / s, (m02 + m20) / s)
+        } else if m11 > m22 {
+            let s = 2. * (1. + m11 - m00 - m22).sqrt();
+            Quaternion::new((m02 - m20) / s, (m01 + m10) / s, 0.25 * s, (m12 + m21) / s)
+        } else {
+            let s = 2. * (1. + m22 - m00 - m11).sqrt();
+            Quaternion::new((m10 - m01) / s, (m02 + m20) / s, (m12 + m21) / s, 0.25 * s)
+        }
+        .normalize()
+    }
+
+    /// Roll/pitch/yaw in radians, via the standard quaternion-to-Euler conversion;
+    /// the `pitch` argument to `asin` is clamped to `[-1, 1]` so floating-point drift
+    /// near a +/-90 degree pitch doesn't produce a NaN.
+    pub fn to_euler(&self) -> (f64, f64, f64) {
+        let (w, x, y, z) = (self.w, self.x, self.y, self.z);
+        let roll = (2. * (w * x + y * z)).atan2(1. - 2. * (x * x + y * y));
+        let pitch = (2. * (w * y - z * x)).max(-1.).min(1.).asin();
+        let yaw = (2. * (w * z + x * y)).atan2(1. - 2. * (y * y + z * z));
+        (roll, pitch, yaw)
+    }
+
+    /// Spherical linear interpolation toward `other` at `t` in `[0, 1]`, falling back
+    /// to linear interpolation when the two orientations are nearly identical, and
+    /// taking the short arc by flipping the sign of `other` when the quaternions are
+    /// more than 90 degrees apart.
+    pub fn slerp(&self, other: Self, t: f64) -> Self {
+        let (q0, mut q1) = (*self, other);
+        let mut dot = q0.dot(q1);
+        if dot < 0. {
+            q1 = Quaternion::new(-q1.w, -q1.x, -q1.y, -q1.z);
+            dot = -dot;
+        }
+
+        let epsilon = 1.0e-6;
+        if dot > 1. - epsilon {
+            return Quaternion::new(
+                q0.w + (q1.w - q0.w) * t,
+                q0.x + (q1.x - q0.x) * t,
+                q0.y + (q1.y - q0.y) * t,
+                q0.z + (q1.z - q0.z) * t,
+            ).normalize();
+        }
+
+        let omega = dot.acos();
+        let sin_omega = omega.sin();
+        let s0 = ((1. - t) * omega).sin() / sin_omega;
+        let s1 = (t * omega).sin() / sin_omega;
+        Quaternion::new(
+            q0.w * s0 + q1.w * s1,
+            q0.x * s0 + q1.x * s1,
+            q0.y * s0 + q1.y * s1,
+            q0.z * s0 + q1.z * s1,
+        )
+    }
+}
+
+impl ops::Mul for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: Self) -> Self {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+impl ops::Add for Quaternion {
+    type Output = Quaternion;
+    fn add(self, rhs: Self) -> Self {
+        Quaternion::new(
+            self.w + rhs.w,
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+        )
+    }
+}
+
+impl ops::Mul<f64> for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: f64) -> Self {
+        Quaternion::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl fmt::Display for Quaternion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(w:{}, x:{}, y:{}, z:{})", self.w, self.x, self.y, self.z)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +444,73 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_quaternion_rotate_around_z_axis() {
+        let q = Quaternion::from_axis_angle(
+            Coordinate {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            },
+            f64::consts::PI / 2.,
+        );
+        let v = Coordinate {
+            x: 1.,
+            y: 0.,
+            z: 0.,
+        };
+        let rotated = q.rotate(v);
+        assert!((rotated.x - 0.).abs() < 1.0e-10);
+        assert!((rotated.y - 1.).abs() < 1.0e-10);
+        assert!((rotated.z - 0.).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn test_quaternion_slerp_endpoints() {
+        let q0 = Quaternion::identity();
+        let q1 = Quaternion::from_axis_angle(
+            Coordinate {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            },
+            f64::consts::PI / 2.,
+        );
+        let start = q0.slerp(q1, 0.);
+        let end = q0.slerp(q1, 1.);
+        assert!((start.w - q0.w).abs() < 1.0e-10);
+        assert!((end.w - q1.w).abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn test_from_basis_level_forward_is_identity() {
+        let q = Quaternion::from_basis(
+            Coordinate { x: 1., y: 0., z: 0. },
+            Coordinate { x: 0., y: 0., z: 1. },
+        );
+        let (roll, pitch, yaw) = q.to_euler();
+        assert!(roll.abs() < 1.0e-10);
+        assert!(pitch.abs() < 1.0e-10);
+        assert!(yaw.abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn test_from_basis_tilted_forward_reports_nonzero_pitch() {
+        let q = Quaternion::from_basis(
+            Coordinate { x: 1., y: 0., z: 1. },
+            Coordinate { x: -1., y: 0., z: 1. },
+        );
+        let (roll, pitch, yaw) = q.to_euler();
+        assert!(roll.abs() < 1.0e-10);
+        assert!((pitch + f64::consts::PI / 4.).abs() < 1.0e-10);
+        assert!(yaw.abs() < 1.0e-10);
+    }
+
+    #[test]
+    fn test_to_euler_clamps_near_gimbal_lock() {
+        let q = Quaternion::new(1., 0., 1., 0.).normalize();
+        let (_, pitch, _) = q.to_euler();
+        assert!(!pitch.is_nan());
+    }
 }
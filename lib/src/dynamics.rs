@@ -3,6 +3,8 @@ use coordinate::Coordinate;
 use somite::Somite;
 use phase_oscillator::PhaseOscillator;
 use path_heights::PathHeights;
+use environment::Environment;
+use body_config::BodyConfig;
 
 /// Dynamics defines mechanical dynamics of a system
 /// 
@@ -33,47 +35,129 @@ const EPSILON: f64 = 10e-5;
 const STUCKED_EPSILON: f64 = 10e-3;
 
 impl Dynamics {
-    /// Calculate shear force caused by friction between a somite and the substrate.
-    pub fn calculate_friction(&self, somite: &Somite, applied_force: &Coordinate) -> Coordinate {
-        Coordinate::new(self.shear_friction(somite.get_verocity(), applied_force), 0., 0.)
+    /// Weight plus medium drag a somite feels from its surroundings: `mass * gravity`
+    /// and a drag term opposing velocity along all three axes (unlike
+    /// `shear_friction`'s ground friction, which only acts along x).
+    pub fn calculate_environmental_force(&self, somite: &Somite, environment: &Environment) -> Coordinate {
+        environment.gravity * somite.mass - somite.get_verocity() * environment.medium_friction_coeff
     }
 
-    /// Calculate force caused by a gripper.
+    /// Offset from a somite's center to its ground/gripper contact point, used to turn
+    /// a contact force into the torque it exerts about the somite's center.
+    fn contact_offset(somite: &Somite) -> Coordinate {
+        Coordinate::new(0., 0., -somite.radius)
+    }
+
+    /// Calculate the wrench (force, torque) caused by friction between a somite and the
+    /// substrate. The force acts at the contact point (`-radius` in z from the center),
+    /// so it also produces a torque about the somite's center.
+    pub fn calculate_friction(&self, somite: &Somite, applied_force: &Coordinate, dt: f64) -> (Coordinate, Coordinate) {
+        let force = Coordinate::new(self.shear_friction(somite, applied_force, dt), 0., 0.);
+        let torque = Self::contact_offset(somite).cross_product(force);
+        (force, torque)
+    }
+
+    /// Calculate the wrench (force, torque) caused by a gripper.
     /// Gripping force is modeled by strong springs.
-    /// The gripping force only acts when gripping.
-    pub fn calculate_gripping_force(&self, somite: &Somite, applied_force: &Coordinate) -> Coordinate {
-        Coordinate::new(
+    /// The gripping force only acts when gripping, and is applied at the contact point,
+    /// so it also produces a torque about the somite's center.
+    pub fn calculate_gripping_force(&self, somite: &Somite, applied_force: &Coordinate) -> (Coordinate, Coordinate) {
+        let force = Coordinate::new(
             self.grip_shear_force(somite.get_gripping_point().unwrap().x, somite.get_position().x, somite.get_verocity().x),
             0.,
             - applied_force.z, // cancel force along z axis if gripping
-        )
+        );
+        let torque = Self::contact_offset(somite).cross_product(force);
+        (force, torque)
     }
 
     fn grip_shear_force(&self, resting_point: f64, current_point: f64, verocity: f64) -> f64 {
         -self.shear_force_k * (current_point - resting_point) - self.shear_force_c * verocity
     }
 
-    fn shear_friction(&self, verocity: Coordinate, applied_force: &Coordinate) -> f64 {
+    /// Stick-slip friction: a somite whose motion over this step's `dt` stays within
+    /// `STUCKED_EPSILON` is treated as at rest (rather than merely slow, which avoided
+    /// chatter between the dynamic- and static-friction branches at vanishing speed)
+    /// and pinned to a resting point, held there by a spring-damper capped at the
+    /// maximum static friction. Once the applied tangential force exceeds that cap,
+    /// the somite slips free into the ordinary dynamic-friction branch and its resting
+    /// point is cleared.
+    fn shear_friction(&self, somite: &Somite, applied_force: &Coordinate, dt: f64) -> f64 {
+        let verocity = somite.get_verocity();
         let normal_force = applied_force.z.min(0.).abs();
-        if verocity.x.abs() > 0. {
-            -verocity.x.signum() * self.dynamic_friction_coeff * normal_force
-                + self.viscosity_friction_coeff * -verocity.x
-        } else {
+        let dynamic_friction = -verocity.x.signum() * self.dynamic_friction_coeff * normal_force
+            + self.viscosity_friction_coeff * -verocity.x;
+
+        if somite.is_stuck() || (verocity.x * dt).abs() < STUCKED_EPSILON {
+            somite.stick();
             let max_static_friction = self.static_friction_coeff * normal_force;
             if applied_force.x.abs() > max_static_friction {
-                max_static_friction * -applied_force.x.signum()
+                somite.unstick();
+                dynamic_friction
             } else {
-                -applied_force.x
+                let resting_x = somite.get_stuck_point().unwrap().x;
+                let restoring = -self.shear_force_k * (somite.get_position().x - resting_x)
+                    - self.shear_force_c * verocity.x;
+                restoring.max(-max_static_friction).min(max_static_friction)
             }
+        } else {
+            somite.unstick();
+            dynamic_friction
+        }
+    }
+
+    /// Viscoelastic force the inner body spring-damper between two adjacent somites
+    /// exerts on `own_position`: a spring pulling the pair toward `rest_length` apart
+    /// plus a damper opposing their closing/separating velocity, both along the axis
+    /// between them. The caller applies the negated force to the neighbor (Newton's
+    /// third law), just as `add_self_collision_forces` does for contact forces.
+    pub fn calculate_inner_spring_force(
+        &self,
+        body: &BodyConfig,
+        own_position: Coordinate,
+        neighbor_position: Coordinate,
+        own_verocity: Coordinate,
+        neighbor_verocity: Coordinate,
+        rest_length: f64,
+    ) -> Coordinate {
+        let d = own_position - neighbor_position;
+        let distance = d.norm();
+        if distance <= 0. {
+            return Coordinate::zero();
         }
+        let direction = d / distance;
+        let relative_velocity = own_verocity - neighbor_verocity;
+        direction
+            * (-body.inner_spring_k * (distance - rest_length)
+                - body.inner_spring_c * relative_velocity.inner_product(direction))
+    }
+
+    /// Spring-damper pulling a somite toward its goal position. Driving `goal_position`
+    /// over time (rather than applying a force directly) is how actuation such as
+    /// peristalsis is expressed as a time-varying resting configuration.
+    pub fn calculate_goal_force(
+        &self,
+        body: &BodyConfig,
+        position: Coordinate,
+        goal_position: Coordinate,
+        verocity: Coordinate,
+    ) -> Coordinate {
+        (goal_position - position) * body.goal_k - verocity * body.goal_friction
+    }
+
+    /// Penalty restoring force for a somite-somite contact with the given `penetration`
+    /// depth and unit `normal`, plus a damping term along the normal component of
+    /// `relative_velocity` so overlapping somites settle instead of oscillating.
+    pub fn calculate_contact_force(&self, penetration: f64, normal: Coordinate, relative_velocity: Coordinate) -> Coordinate {
+        normal * (self.shear_force_k * penetration - self.shear_force_c * relative_velocity.inner_product(normal))
     }
 
-    pub fn is_blocked_by_obstacle(&self, somite: &Somite, path_height: &PathHeights) -> bool {
-        somite.get_position().z < somite.radius + path_height.get_height(somite.get_position().x) - EPSILON
+    pub fn is_blocked_by_obstacle(&self, somite: &Somite, path_height: &PathHeights, t: f64) -> bool {
+        somite.get_position().z < somite.radius + path_height.get_height(somite.get_position().x, t) - EPSILON
     }
 
-    pub fn should_grip(&self, somite: &Somite, oscillator: Ref<PhaseOscillator>, path_heights: &PathHeights) -> bool {
-        if oscillator.get_phase().sin() < self.grip_phase_threshold && path_heights.is_on_ground(somite, self.is_blocked_by_obstacle(somite, path_heights))
+    pub fn should_grip(&self, somite: &Somite, oscillator: Ref<PhaseOscillator>, path_heights: &PathHeights, t: f64) -> bool {
+        if oscillator.get_phase().sin() < self.grip_phase_threshold && path_heights.is_on_ground(somite, t)
             && !somite.is_gripping()
         {
             true
@@ -89,6 +173,75 @@ impl Dynamics {
             false
         }
     }
+
+    /// Resolve every active somite-ground contact as a unilateral constraint
+    /// `0 <= gap ⊥ lambda_n >= 0` (gap `= z - radius - ground_height`) by projected
+    /// Gauss-Seidel: each sweep recomputes every active contact's normal impulse from
+    /// its somite's current velocity (driving post-impulse normal velocity to
+    /// `-restitution` times its pre-impulse value), clamps it to `>= 0`, then projects
+    /// the tangential impulse onto the Coulomb friction cone `|lambda_t| <= mu *
+    /// lambda_n` using `static_friction_coeff`. Repeats until the largest normal
+    /// impulse correction in a sweep falls under `tolerance` or `max_iterations` is
+    /// reached. Applies the impulses directly to each contacting somite's velocity and
+    /// returns the per-somite normal impulse (0 for somites not in contact), so
+    /// callers can read true ground reaction through `Caterpillar::contact_forces`.
+    pub fn resolve_complementarity_contacts(
+        &self,
+        somites: &[Somite],
+        path_heights: &PathHeights,
+        t: f64,
+        restitution: f64,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Vec<f64> {
+        let active: Vec<usize> = somites
+            .iter()
+            .enumerate()
+            .filter(|&(_, s)| s.get_position().z - s.radius <= path_heights.get_height(s.get_position().x, t))
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut normal_impulses = vec![0.; somites.len()];
+        let mut tangential_impulses = vec![0.; somites.len()];
+        if active.is_empty() {
+            return normal_impulses;
+        }
+
+        for _ in 0..max_iterations {
+            let mut max_correction = 0.0_f64;
+            for &i in &active {
+                let s = &somites[i];
+                let verocity = s.get_verocity();
+
+                let target_normal_velocity = if verocity.z < 0. {
+                    -restitution * verocity.z
+                } else {
+                    verocity.z
+                };
+                let new_normal_impulse = (normal_impulses[i] + s.mass * (target_normal_velocity - verocity.z)).max(0.);
+                let applied_normal_impulse = new_normal_impulse - normal_impulses[i];
+
+                let friction_limit = self.static_friction_coeff * new_normal_impulse;
+                let new_tangential_impulse = (tangential_impulses[i] - s.mass * verocity.x).max(-friction_limit).min(friction_limit);
+                let applied_tangential_impulse = new_tangential_impulse - tangential_impulses[i];
+                tangential_impulses[i] = new_tangential_impulse;
+
+                s.set_verocity(Coordinate::new(
+                    verocity.x + applied_tangential_impulse / s.mass,
+                    verocity.y,
+                    verocity.z + applied_normal_impulse / s.mass,
+                ));
+
+                max_correction = max_correction.max(applied_normal_impulse.abs());
+                normal_impulses[i] = new_normal_impulse;
+            }
+            if max_correction < tolerance {
+                break;
+            }
+        }
+
+        normal_impulses
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +252,83 @@ mod test {
     use coordinate::Coordinate;
     use somite::Somite;
     use phase_oscillator::PhaseOscillator;
+    use environment::Environment;
+
+    #[test]
+    fn test_calculate_environmental_force() {
+        let d = Dynamics {..Default::default()};
+        let s = Somite::new(1., 2., Coordinate::new(0., 0., 1.), Coordinate::new(1., -1., 2.));
+        let env = Environment::new(Coordinate::new(0., 0., -9.8), 0.5);
+        let force = d.calculate_environmental_force(&s, &env);
+        let expected = Coordinate::new(-0.5, 0.5, -2. * 9.8 - 1.);
+        assert_eq!(force, expected);
+    }
+
+    #[test]
+    fn test_calculate_inner_spring_force_rest_length_equilibrium() {
+        let d = Dynamics {..Default::default()};
+        let body = BodyConfig::new(10., 2., 0., 0.);
+        let force = d.calculate_inner_spring_force(
+            &body,
+            Coordinate::new(0., 0., 0.),
+            Coordinate::new(1., 0., 0.),
+            Coordinate::zero(),
+            Coordinate::zero(),
+            1.,
+        );
+        assert_eq!(force, Coordinate::zero());
+    }
+
+    #[test]
+    fn test_calculate_inner_spring_force_equal_and_opposite() {
+        let d = Dynamics {..Default::default()};
+        let body = BodyConfig::new(10., 2., 0., 0.);
+        let own_position = Coordinate::new(0., 0., 0.);
+        let neighbor_position = Coordinate::new(1.5, 0., 0.);
+        let own_verocity = Coordinate::new(-1., 0., 0.);
+        let neighbor_verocity = Coordinate::new(1., 0., 0.);
+
+        let own_force = d.calculate_inner_spring_force(
+            &body, own_position, neighbor_position, own_verocity, neighbor_verocity, 1.,
+        );
+        let neighbor_force = d.calculate_inner_spring_force(
+            &body, neighbor_position, own_position, neighbor_verocity, own_verocity, 1.,
+        );
+
+        // stretched past rest length and separating: both the spring and the damper
+        // should pull the pair back together
+        let expected = Coordinate::new(9., 0., 0.);
+        assert_eq!(own_force, expected);
+        assert_eq!(neighbor_force, -own_force, "forces on the pair must be equal and opposite");
+    }
+
+    #[test]
+    fn test_calculate_goal_force() {
+        let d = Dynamics {..Default::default()};
+        let body = BodyConfig::new(0., 0., 5., 3.);
+        let force = d.calculate_goal_force(
+            &body,
+            Coordinate::new(1., 0., 0.),
+            Coordinate::new(2., 0., 0.),
+            Coordinate::new(0.5, 0., 0.),
+        );
+        let expected = Coordinate::new(5. * 1. - 3. * 0.5, 0., 0.);
+        assert_eq!(force, expected);
+    }
+
+    #[test]
+    fn test_calculate_contact_force() {
+        let d = Dynamics {
+            shear_force_k: 10.,
+            shear_force_c: 2.,
+            ..Default::default()
+        };
+        let normal = Coordinate::new(1., 0., 0.);
+        let relative_velocity = Coordinate::new(-3., 0., 0.);
+        let force = d.calculate_contact_force(0.5, normal, relative_velocity);
+        let expected = Coordinate::new(d.shear_force_k * 0.5 - d.shear_force_c * -3., 0., 0.);
+        assert_eq!(force, expected);
+    }
 
     #[test]
     fn test_is_blocked_by_obstacle() {
@@ -113,15 +343,50 @@ mod test {
         path_heights.set(0.5, 0.7).unwrap();
         
         // not blocked
-        assert!(!d.is_blocked_by_obstacle(&s, &path_heights));
+        assert!(!d.is_blocked_by_obstacle(&s, &path_heights, 0.));
 
         // blocked
         s.set_position(Coordinate::new(0.51, 0., 1.));
-        assert!(d.is_blocked_by_obstacle(&s, &path_heights));
+        assert!(d.is_blocked_by_obstacle(&s, &path_heights, 0.));
 
         // not blocked
         s.set_position(Coordinate::new(0.51, 0., 1.7));
-        assert!(!d.is_blocked_by_obstacle(&s, &path_heights));
+        assert!(!d.is_blocked_by_obstacle(&s, &path_heights, 0.));
+    }
+
+    #[test]
+    fn test_contact_distinguishes_riser_from_floor() {
+        let mut path_heights = PathHeights::new();
+        path_heights.set(0.5, 1.).unwrap(); // a step rising from 0 to 1 at x=0.5
+
+        // below the plateau and within one radius of the step: touching the riser
+        let s = Somite::new(1., 1., Coordinate::new(0.51, 0., 0.5), Coordinate::zero());
+        let contact = path_heights.contact(&s, 0.).unwrap();
+        assert_eq!(contact.normal, Coordinate::new(-1., 0., 0.));
+        assert!(contact.penetration > 0.);
+
+        // standing on the lower section's own floor, well clear of the step: no riser
+        let s = Somite::new(1., 1., Coordinate::new(0.1, 0., 0.99), Coordinate::zero());
+        let contact = path_heights.contact(&s, 0.).unwrap();
+        assert_eq!(contact.normal, Coordinate::new(0., 0., 1.));
+
+        // above the plateau: no contact at all
+        let s = Somite::new(1., 1., Coordinate::new(0.51, 0., 3.), Coordinate::zero());
+        assert!(path_heights.contact(&s, 0.).is_none());
+    }
+
+    #[test]
+    fn test_is_blocked_by_obstacle_tracks_moving_platform() {
+        let d = Dynamics {..Default::default()};
+        let s = Somite::new(1., 1., Coordinate::new(0.51, 0., 1.1), Coordinate::zero());
+        let mut path_heights = PathHeights::new();
+        path_heights.set_moving(0.5, 0.7, 0.8, 1., f64::consts::FRAC_PI_2).unwrap();
+
+        // t=0: height is base_height + amplitude*cos(0) = 1.5, so the somite is blocked
+        assert!(d.is_blocked_by_obstacle(&s, &path_heights, 0.));
+
+        // half a period later the platform has swung down to base_height - amplitude = -0.1
+        assert!(!d.is_blocked_by_obstacle(&s, &path_heights, 0.5));
     }
 
     #[test]
@@ -140,13 +405,17 @@ mod test {
         s.grip();
         s.set_position(Coordinate::new(1., 0., 1.));
         let force_applied = Coordinate::new(5., 0., -6.);
-        let gripping_force = d.calculate_gripping_force(&s, &force_applied);
+        let (gripping_force, torque) = d.calculate_gripping_force(&s, &force_applied);
         let expected = Coordinate::new(-d.shear_force_c * -2. + -d.shear_force_k * 1., 0., 6.);
         assert_eq!(
             gripping_force, expected,
             "while gripping, expected {}, got {}",
             expected, gripping_force
         );
+        // contact offset (0, 0, -radius) crossed with a force confined to the x-z plane
+        // yields a torque purely about y
+        let expected_torque = Coordinate::new(0., -expected.x, 0.);
+        assert_eq!(torque, expected_torque);
     }
 
     #[test]
@@ -158,7 +427,7 @@ mod test {
         };
         let s = Somite::new(1., 1., Coordinate::new(0., 0., 1.), Coordinate::new(-2., 0., 0.));
         let force_applied = Coordinate::new(5., 0., -6.);
-        let friction = d.calculate_friction(&s, &force_applied);
+        let (friction, torque) = d.calculate_friction(&s, &force_applied, 1.0);
         let expected = Coordinate::new(
             -d.dynamic_friction_coeff * (-6.0_f64).abs() * (-1.) - d.viscosity_friction_coeff * (-2.),
             0.,
@@ -169,40 +438,67 @@ mod test {
             "while released and moving, expected {}, got {}",
             expected, friction
         );
+        assert_eq!(torque, Coordinate::new(0., -expected.x, 0.));
+        assert!(!s.is_stuck(), "moving fast enough that it should not be pinned");
     }
 
     #[test]
-    fn test_calculate_friction_static() {
+    fn test_calculate_friction_sticks_when_slow() {
         let d = Dynamics {
             static_friction_coeff: 3.,
+            shear_force_k: 10.,
+            shear_force_c: 2.,
             ..Default::default()
         };
         let s = Somite::new(1., 1., Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., 0.));
         let force_applied = Coordinate::new(5., 0., -6.);
-        let friction = d.calculate_friction(&s, &force_applied);
-        let expected = Coordinate::new(-5., 0., 0.);
+        let (friction, _) = d.calculate_friction(&s, &force_applied, 0.01);
+        // just pinned this step, so the resting point equals the current position and
+        // the spring-damper contributes nothing yet
+        assert_eq!(friction, Coordinate::zero());
+        assert!(s.is_stuck());
+    }
+
+    #[test]
+    fn test_calculate_friction_holds_under_small_disturbance() {
+        let d = Dynamics {
+            static_friction_coeff: 3.,
+            shear_force_k: 10.,
+            shear_force_c: 2.,
+            ..Default::default()
+        };
+        let s = Somite::new(1., 1., Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., 0.));
+        s.stick();
+        s.set_position(Coordinate::new(0.05, 0., 1.)); // nudged slightly off its resting point
+        let force_applied = Coordinate::new(1., 0., -6.); // well under the static cap of 3*6=18
+        let (friction, _) = d.calculate_friction(&s, &force_applied, 0.01);
+        let expected = Coordinate::new(-d.shear_force_k * 0.05, 0., 0.);
         assert_eq!(
             friction, expected,
-            "while released and moving, expected {}, got {}",
+            "while pinned under a small disturbance, expected {}, got {}",
             expected, friction
         );
+        assert!(s.is_stuck(), "should remain pinned under a small disturbing force");
     }
 
     #[test]
-    fn test_calculate_friction_maximum_static() {
+    fn test_calculate_friction_releases_past_static_cap() {
         let d = Dynamics {
             static_friction_coeff: 3.,
+            dynamic_friction_coeff: 7.,
             ..Default::default()
         };
-        let s = Somite::new( 1., 1., Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., 0.));
-        let force_applied = Coordinate::new(20., 0., -6.);
-        let friction = d.calculate_friction(&s, &force_applied);
-        let expected = Coordinate::new( -d.static_friction_coeff * (-6.0_f64).abs() * (20.0_f64).signum(), 0., 0.);
+        let s = Somite::new(1., 1., Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., 0.));
+        s.stick();
+        let force_applied = Coordinate::new(20., 0., -6.); // exceeds the static cap of 3*6=18
+        let (friction, _) = d.calculate_friction(&s, &force_applied, 0.01);
+        let expected = Coordinate::new(-d.dynamic_friction_coeff * (-6.0_f64).abs() * (0.0_f64).signum(), 0., 0.);
         assert_eq!(
             friction, expected,
-            "while released, moving, and applied force is larger than max static friction, expected {}, got {}",
+            "once the applied force exceeds the static cap, expected {}, got {}",
             expected, friction
         );
+        assert!(!s.is_stuck(), "should slip free once the static cap is exceeded");
     }
 
     #[test]
@@ -217,33 +513,33 @@ mod test {
         s.set_position(Coordinate::new(0., 0., 1.1));
         o.borrow_mut().set_phase(f64::consts::PI);
         assert!(
-            !d.should_grip(&s, o.borrow(), &PathHeights::new()),
+            !d.should_grip(&s, o.borrow(), &PathHeights::new(), 0.),
             "in the air & out of grip range"
         ); 
 
         o.borrow_mut().set_phase(3. / 2. * f64::consts::PI);
         assert!(
-            !d.should_grip(&s, o.borrow(), &PathHeights::new()),
+            !d.should_grip(&s, o.borrow(), &PathHeights::new(), 0.),
             "in the air & in the grip range"
         );
 
         s.set_position(Coordinate::new(0., 0., 1.));
         o.borrow_mut().set_phase(f64::consts::PI);
         assert!(
-            !d.should_grip(&s, o.borrow(), &PathHeights::new()),
+            !d.should_grip(&s, o.borrow(), &PathHeights::new(), 0.),
             "on the ground & out of grip range"
         );
 
         o.borrow_mut().set_phase(3. / 2. * f64::consts::PI);
         s.grip();
         assert!(
-            !d.should_grip(&s, o.borrow(), &PathHeights::new()),
+            !d.should_grip(&s, o.borrow(), &PathHeights::new(), 0.),
             "on the ground & in the grip range & gripping"
         );
 
         s.release();
         assert!(
-            d.should_grip(&s, o.borrow(), &PathHeights::new()),
+            d.should_grip(&s, o.borrow(), &PathHeights::new(), 0.),
             "on the ground & in the grip range & not gripping"
         );
     }
@@ -295,4 +591,29 @@ mod test {
             "on the ground & in the grip range & not gripping"
         );
     }
+
+    #[test]
+    fn test_resolve_complementarity_contacts_ignores_somites_in_the_air() {
+        let d = Dynamics { ..Default::default() };
+        let somites = vec![Somite::new(1., 1., Coordinate::new(0., 0., 5.), Coordinate::new(0., 0., -1.))];
+        let impulses = d.resolve_complementarity_contacts(&somites, &PathHeights::new(), 0., 0., 50, 1.0e-9);
+        assert_eq!(impulses, vec![0.]);
+    }
+
+    #[test]
+    fn test_resolve_complementarity_contacts_zeros_penetrating_velocity() {
+        let d = Dynamics { static_friction_coeff: 10., ..Default::default() };
+        let somites = vec![Somite::new(1., 1., Coordinate::new(0., 0., 0.9), Coordinate::new(0., 0., -2.))];
+        let impulses = d.resolve_complementarity_contacts(&somites, &PathHeights::new(), 0., 0., 50, 1.0e-9);
+        assert!(impulses[0] > 0., "a contacting, penetrating somite should get a positive normal impulse");
+        assert_eq!(somites[0].get_verocity().z, 0., "zero restitution should fully absorb the approaching normal velocity");
+    }
+
+    #[test]
+    fn test_resolve_complementarity_contacts_clamps_tangential_impulse_to_friction_cone() {
+        let d = Dynamics { static_friction_coeff: 0.1, ..Default::default() };
+        let somites = vec![Somite::new(1., 1., Coordinate::new(0., 0., 0.9), Coordinate::new(10., 0., -2.))];
+        d.resolve_complementarity_contacts(&somites, &PathHeights::new(), 0., 0., 50, 1.0e-9);
+        assert!(somites[0].get_verocity().x > 0., "friction should only partially cancel tangential velocity once the cone caps it");
+    }
 }
@@ -0,0 +1,52 @@
+use coordinate::Coordinate;
+
+const GRAVITATIONAL_ACCELERATION: f64 = 9.8065;
+
+/// Ambient conditions shared by every somite: a gravity vector, so tilting or
+/// scaling gravity doesn't require every caller to re-derive a normal force by
+/// hand, and a medium friction coefficient modeling drag through a surrounding
+/// fluid (air, water, ...).
+///
+/// # Example
+///
+/// ```
+/// let env = Environment{
+///     gravity: Coordinate::new(0., 0., -9.8065),
+///     medium_friction_coeff: 0.2,
+/// };
+/// ```
+///
+pub struct Environment {
+    pub gravity: Coordinate,
+    pub medium_friction_coeff: f64,
+}
+
+impl Environment {
+    pub fn new(gravity: Coordinate, medium_friction_coeff: f64) -> Self {
+        Environment {
+            gravity: gravity,
+            medium_friction_coeff: medium_friction_coeff,
+        }
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment {
+            gravity: Coordinate::new(0., 0., -GRAVITATIONAL_ACCELERATION),
+            medium_friction_coeff: 0.,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_gravity_points_down() {
+        let env = Environment::default();
+        assert_eq!(env.gravity, Coordinate::new(0., 0., -GRAVITATIONAL_ACCELERATION));
+        assert_eq!(env.medium_friction_coeff, 0.);
+    }
+}
@@ -0,0 +1,78 @@
+extern crate hdf5;
+
+use std::collections::HashMap;
+use caterpillar_config::Config;
+use path_heights::PathHeights;
+use simulation_export::{Object, ObjectPosition};
+
+/// Write a full recorded simulation out as an HDF5 file for interchange with
+/// pandas/h5py, as an alternative to `SimulationProc::save`'s custom JSON format.
+/// Per-frame quantities (position, oscillator phases, tensions, gripping state) are
+/// written as `[frame, object]` (or `[frame, object, 3]` for vectors) datasets, sorted
+/// by frame order; run-level metadata (`dt`, `somite_count`, `gravity_angle`,
+/// `somite_radius`) is attached as top-level attributes.
+pub fn save_simulation_hdf5(
+    file_path: &str,
+    objects: &[Object],
+    frames: &HashMap<usize, Vec<ObjectPosition>>,
+    config: &Config,
+    gravity_angle: f64,
+) -> hdf5::Result<()> {
+    let file = hdf5::File::create(file_path)?;
+
+    file.new_attr::<f64>().create("dt")?.write_scalar(&config.time_delta)?;
+    file.new_attr::<usize>().create("somite_count")?.write_scalar(&objects.len())?;
+    file.new_attr::<f64>().create("gravity_angle")?.write_scalar(&gravity_angle)?;
+    file.new_attr::<f64>().create("somite_radius")?.write_scalar(&config.somite_radius)?;
+
+    let mut frame_orders: Vec<usize> = frames.keys().cloned().collect();
+    frame_orders.sort();
+    let n_frames = frame_orders.len();
+    let n_objects = objects.len();
+
+    let mut positions = vec![0.; n_frames * n_objects * 3];
+    let mut gripping = vec![0u8; n_frames * n_objects];
+    let mut phases = vec![0.; n_frames * n_objects];
+    let mut tensions = vec![0.; n_frames * n_objects];
+
+    for (fi, &order) in frame_orders.iter().enumerate() {
+        for object_position in &frames[&order] {
+            if let Some(oi) = objects.iter().position(|o| o.id == object_position.id) {
+                let base = (fi * n_objects + oi) * 3;
+                positions[base] = object_position.pos.0;
+                positions[base + 1] = object_position.pos.1;
+                positions[base + 2] = object_position.pos.2;
+                gripping[fi * n_objects + oi] = object_position.gripping as u8;
+                phases[fi * n_objects + oi] = object_position.phase.unwrap_or(0.);
+                tensions[fi * n_objects + oi] = object_position.tension.unwrap_or(0.);
+            }
+        }
+    }
+
+    file.new_dataset::<f64>().shape((n_frames, n_objects, 3)).create("positions")?.write(&positions)?;
+    file.new_dataset::<u8>().shape((n_frames, n_objects)).create("gripping")?.write(&gripping)?;
+    file.new_dataset::<f64>().shape((n_frames, n_objects)).create("phases")?.write(&phases)?;
+    file.new_dataset::<f64>().shape((n_frames, n_objects)).create("tensions")?.write(&tensions)?;
+    file.new_dataset::<usize>().shape(n_frames).create("frame_order")?.write(&frame_orders)?;
+
+    let radii = objects.iter().map(|o| o.rad).collect::<Vec<f64>>();
+    file.new_dataset::<f64>().shape(n_objects).create("radii")?.write(&radii)?;
+
+    Ok(())
+}
+
+/// Load a terrain profile from an HDF5 file holding equal-length `start_points` and
+/// `heights` datasets, as an alternative to `Caterpillar::parse_path_heights`'s
+/// PyDict-based ingestion — for driving long experiments from large pre-generated
+/// terrain files instead of building the dict in Python.
+pub fn load_path_heights_hdf5(file_path: &str) -> hdf5::Result<PathHeights> {
+    let file = hdf5::File::open(file_path)?;
+    let start_points: Vec<f64> = file.dataset("start_points")?.read_raw()?;
+    let heights: Vec<f64> = file.dataset("heights")?.read_raw()?;
+
+    let mut path_heights = PathHeights::new();
+    for (&start_point, &height) in start_points.iter().zip(heights.iter()) {
+        path_heights.set(start_point, height).unwrap();
+    }
+    Ok(path_heights)
+}
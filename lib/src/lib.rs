@@ -9,13 +9,11 @@ extern crate serde_derive;
 use std::f64;
 use std::cell;
 use std::collections;
-use cpython::{PyDict, PyObject, PyResult, PyString, PyTuple, Python, PythonObject, ToPyObject, PyErr};
+use cpython::{PyDict, PyList, PyObject, PyResult, PyString, PyTuple, Python, PythonObject, ToPyObject, PyErr};
 
 mod phase_oscillator;
 mod torsion_spring;
 mod somite;
-mod spring;
-mod dumper;
 mod caterpillar_config;
 mod coordinate;
 mod simulation_export;
@@ -23,14 +21,35 @@ mod calculations;
 mod dynamics;
 mod path_heights;
 mod profile_tools;
-
-use coordinate::Coordinate;
+mod ops;
+mod angle;
+mod collision;
+mod environment;
+mod body_config;
+mod integrator;
+mod phase_coupling;
+mod contact_model;
+mod step_logger;
+mod hdf5_export;
+mod mesh_terrain;
+
+use coordinate::{Coordinate, Quaternion};
 use phase_oscillator::PhaseOscillator;
 use dynamics::Dynamics;
+use body_config::BodyConfig;
+use integrator::Integrator;
+use phase_coupling::PhaseCoupling;
+use contact_model::ContactModel;
+use step_logger::{StepLogger, LogColumn};
+use mesh_terrain::MeshTerrain;
 use path_heights::PathHeights;
+use environment::Environment;
 use profile_tools::TimeProfiler;
 
 const GRAVITATIONAL_ACCELERATION: f64 = 9.8065;
+// bound on how many times update_state will bisect a single time_delta chasing
+// successive ground crossings within it
+const GROUND_CONTACT_SUBSTEP_DEPTH: usize = 8;
 
 py_module_initializer!(caterpillar, initcaterpillar, PyInit_caterpillar, |py, m| {
     try!(m.add(
@@ -48,7 +67,8 @@ py_module_initializer!(caterpillar, initcaterpillar, PyInit_caterpillar, |py, m|
 /// config                                      holds config data given from Python caller
 /// somites                                     Vec of somite objects
 /// simulation_protocol                         object to save simulation result
-/// frame_count            
+/// frame_count
+/// simulation_time                             simulation clock advanced by update_state's time_delta each step; read by path_heights queries for moving-platform sections
 /// temp_forces                                 Vec of force object to hold external force applied on each somite until next step
 /// oscillators                                 HashMap to somite id and PhaseOscillator objects
 /// oscillator_ids                              Vec of somite ids where oscillators are assigned
@@ -61,6 +81,10 @@ py_module_initializer!(caterpillar, initcaterpillar, PyInit_caterpillar, |py, m|
 /// gripping_thresholds                         HashMap of gripper somite ids and their gripping thresholds
 /// previous_vertical_torsion_spring_angles     
 /// gravity_angle                               f64 to save gravity direction. 0 corresponds to locomotion on flat plain, 0~pi means climbing, pi means upside-down, pi~2pi means descending. default to 0
+/// gravity_vector                              full 3D unit gravity direction, kept in sync with gravity_angle unless overridden by set_gravity_vector
+/// mesh_terrain                                triangle-mesh terrain populated via add_terrain_triangle; empty until loaded, in which case obstacle contact falls back to path_heights
+/// ground_contact_forces                       per-somite vertical ground contact force from the compliant penalty model (config.contact_k/contact_c), read through contact_force_z()
+/// grasp_wrench                                net (force, torque) of the current step's gripping/ground-contact wrenches about the center of mass, in the body frame, read through resultant_wrench()
 /// dynamics                                    struct that defines mechanical dynamics
 /// path_heights                                holds height of each section in a path
 /// somite_distances                            holds inter segment distances at each step
@@ -77,8 +101,10 @@ py_module_initializer!(caterpillar, initcaterpillar, PyInit_caterpillar, |py, m|
 /// set_force_on_somite(&self, somite_number: usize, force: (f64, f64, f64)) -> PyResult<PyObject> 
 /// set_oscillation_ranges(&self, angle_ranges: PyTuple) -> PyResult<PyObject> 
 /// set_gripping_phase_thresholds(&self, phase_thresholds: PyTuple) -> PyResult<PyObject> 
-/// set_target_angle(&self, target_somite_oscillartor: usize, target_angle: f64) -> PyResult<PyObject> 
-/// step(&self, dt: f64) -> PyResult<PyObject> 
+/// set_target_angle(&self, target_somite_oscillartor: usize, target_angle: f64) -> PyResult<PyObject>
+/// set_goal_position(&self, somite_id: usize, x: f64, y: f64, z: f64) -> PyResult<PyObject>
+/// clear_goal_position(&self, somite_id: usize) -> PyResult<PyObject>
+/// step(&self, dt: f64) -> PyResult<PyObject>
 /// step_with_feedbacks(&self, dt: f64, feedbacks_somites: PyTuple, feedbacks_grippers: PyTuple) -> PyResult<PyObject> 
 /// steps_with_feedbacks(&self, dt: f64, steps: u8, feedbacks_somites: PyTuple, feedbacks_grippers: PyTuple) -> PyResult<PyObject>
 /// step_with_target_angles(&self, dt: f64, somite_target_angles: PyTuple, gripper_target_angles: PyTuple) -> PyResult<PyObject> 
@@ -89,6 +115,11 @@ py_module_initializer!(caterpillar, initcaterpillar, PyInit_caterpillar, |py, m|
 /// somite_phases(&self) -> PyResult<PyTuple> 
 /// gripper_phases(&self) -> PyResult<PyTuple> 
 /// set_gravity_angle(&self, new_angle: f64) -> PyResult<PyObject>
+/// set_gravity_vector(&self, x: f64, y: f64, z: f64) -> PyResult<PyObject>
+/// body_orientation(&self) -> PyResult<PyTuple>
+/// add_terrain_triangle(&self, ax: f64, ay: f64, az: f64, bx: f64, by: f64, bz: f64, cx: f64, cy: f64, cz: f64) -> PyResult<PyObject>
+/// contact_force_z(&self) -> PyResult<PyTuple>
+/// resultant_wrench(&self) -> PyResult<PyTuple>
 /// is_on_ground(&self) -> PyResult<bool>
 /// is_head_blocked(&self) -> PyResult<bool>
 /// get_somite_distances(&self) -> PyResult<PyTuple>
@@ -99,6 +130,10 @@ py_class!(class Caterpillar |py| {
     data somites: Vec<somite::Somite>;
     data simulation_protocol: simulation_export::SimulationProc;
     data frame_count: cell::Cell<usize>;
+    // simulation clock, advanced by update_state's time_delta each step; read by
+    // path_heights queries so moving-platform sections (see PathHeights::set_moving)
+    // evaluate at the right instant
+    data simulation_time: cell::Cell<f64>;
     data temp_forces: Vec<cell::Cell<Coordinate>>;
     data oscillators: Vec<PhaseOscillator>;
     data oscillator_ids: Vec<usize>;
@@ -111,18 +146,57 @@ py_class!(class Caterpillar |py| {
     data gripping_thresholds: collections::HashMap<usize, cell::Cell<f64>>;
     data previous_vertical_torsion_spring_angles: Vec<cell::Cell<f64>>;
     data gravity_angle: cell::Cell<f64>;
+    // full 3D gravity direction (unit vector), kept in sync with gravity_angle by
+    // set_gravity_angle and overridden directly by set_gravity_vector; used by
+    // add_gravitational_forces instead of the angle whenever the two diverge
+    data gravity_vector: cell::Cell<Coordinate>;
     data dynamics: Dynamics;
     data path_heights: PathHeights;
     data somite_distances: Vec<cell::Cell<f64>>;
     data somite_angles: Vec<cell::Cell<f64>>;
     data profiler: cell::RefCell<TimeProfiler<'static>>;
+    data integrator: Integrator;
+    // periodic callbacks registered via register_periodic_callback, keyed by the step
+    // interval (in frame_count) they fire on
+    data periodic_callbacks: cell::RefCell<Vec<(usize, PyObject)>>;
+    // Kuramoto-style phase coupling between body actuators / between grippers, set via
+    // set_coupling_weights/set_phase_biases and set_gripping_coupling_weights/
+    // set_gripping_phase_biases; zero (no coupling) until a caller configures them.
+    data oscillator_coupling: PhaseCoupling;
+    data gripping_oscillator_coupling: PhaseCoupling;
+    // ground-contact model chosen at construction time (see the `contact_model`
+    // constructor argument); Penalty (the long-standing behavior) unless overridden
+    data contact_model: ContactModel;
+    // per-somite normal impulse from the most recent complementarity contact
+    // resolution, read through contact_forces(); stays 0 under the Penalty model
+    data contact_normal_impulses: Vec<cell::Cell<f64>>;
+    // per-step CSV logging configured via enable_logging/flush_log; disabled (no
+    // columns) until enable_logging is called
+    data step_logger: cell::RefCell<StepLogger>;
+    // triangle-mesh terrain, populated via add_terrain_triangle; empty (the default)
+    // means no mesh is loaded and obstacle/ground contact falls back to path_heights
+    data mesh_terrain: cell::RefCell<MeshTerrain>;
+    // per-somite vertical ground contact force from the most recent step's compliant
+    // penalty model (see mask_force_on_landing), read through contact_force_z(); 0
+    // when config.contact_k is unset (the hard-clamp fallback) or the somite isn't
+    // touching the ground
+    data ground_contact_forces: Vec<cell::Cell<f64>>;
+    // net (force, torque) of this step's gripping and ground-contact wrenches about
+    // calculate_center_of_mass, expressed in the body frame; accumulated by
+    // accumulate_resultant_wrench and read through resultant_wrench()
+    data grasp_wrench: cell::Cell<(Coordinate, Coordinate)>;
     def __new__(
         _cls,
         somite_number: usize,
         somites_to_set_oscillater: &PyTuple,
         somites_to_set_gripper: &PyTuple,
         kwargs: Option<&PyDict>,
-        heights:  Option<&PyDict>
+        heights:  Option<&PyDict>,
+        integrator: Option<&str>,
+        contact_model: Option<&str>,
+        terrain_hdf5_path: Option<&str>,
+        interpolation_mode: Option<&str>,
+        moving_sections: Option<&PyList>
     ) -> PyResult<Caterpillar> {
         // parse config
         let config = match kwargs {
@@ -130,11 +204,68 @@ py_class!(class Caterpillar |py| {
             None => caterpillar_config::Config::new(),
         };
 
-        // parse path heights info
-        let path_heights = match heights {
-            Some(heights) => Self::parse_path_heights(py, heights),
-            None => PathHeights::new(),
+        // parse the integrator mode; default to explicit Verlet for backward compatibility
+        let integrator = match integrator {
+            Some(name) => match Integrator::from_name(name) {
+                Ok(integrator) => integrator,
+                Err(message) => return Err(PyErr::new::<PyString, _>(py, &message)),
+            },
+            None => Integrator::default(),
+        };
+
+        // parse the ground-contact model; default to the long-standing penalty model
+        let contact_model = match contact_model {
+            Some(name) => match ContactModel::from_name(name) {
+                Ok(contact_model) => contact_model,
+                Err(message) => return Err(PyErr::new::<PyString, _>(py, &message)),
+            },
+            None => ContactModel::default(),
+        };
+
+        // parse path heights info; an HDF5 terrain file (for large pre-generated
+        // terrains) takes precedence over the inline PyDict
+        let mut path_heights = match terrain_hdf5_path {
+            Some(path) => match hdf5_export::load_path_heights_hdf5(path) {
+                Ok(path_heights) => path_heights,
+                Err(e) => return Err(PyErr::new::<PyString, _>(py, &format!("failed to load terrain from {}: {}", path, e))),
+            },
+            None => match heights {
+                Some(heights) => Self::parse_path_heights(py, heights),
+                None => PathHeights::new(),
+            },
+        };
+
+        // select step vs. linear ground interpolation; default to Step so existing runs
+        // stay bit-for-bit comparable
+        let interpolation_mode = match interpolation_mode {
+            Some(name) => match path_heights::InterpolationMode::from_name(name) {
+                Ok(interpolation_mode) => interpolation_mode,
+                Err(message) => return Err(PyErr::new::<PyString, _>(py, &message)),
+            },
+            None => path_heights::InterpolationMode::default(),
         };
+        path_heights.set_profile(interpolation_mode);
+
+        // layer moving platform sections (see PathHeights::set_moving) on top of the
+        // static/HDF5-loaded terrain
+        if let Some(moving_sections) = moving_sections {
+            for section in moving_sections.iter(py) {
+                let section = section.extract::<PyTuple>(py).unwrap();
+                if section.len(py) != 5 {
+                    return Err(PyErr::new::<PyString, _>(py, &"each moving_sections entry must be (start_point, base_height, amplitude, period, phase)".to_string()));
+                }
+                let result = path_heights.set_moving(
+                    section.get_item(py, 0).extract::<f64>(py).unwrap(),
+                    section.get_item(py, 1).extract::<f64>(py).unwrap(),
+                    section.get_item(py, 2).extract::<f64>(py).unwrap(),
+                    section.get_item(py, 3).extract::<f64>(py).unwrap(),
+                    section.get_item(py, 4).extract::<f64>(py).unwrap(),
+                );
+                if let Err(message) = result {
+                    return Err(PyErr::new::<PyString, _>(py, &message));
+                }
+            }
+        }
 
         // create a vect of somites objects
         // ordered from the tail to the head
@@ -226,12 +357,21 @@ py_class!(class Caterpillar |py| {
         let somite_distances = vec![cell::Cell::<f64>::new(config.somite_radius*2.0); somite_number - 1];
         let somite_angles = vec![cell::Cell::<f64>::new(0.0); somite_number - 2];
 
+        // phase coupling between oscillators within each group; uncoupled (all zero) until
+        // set_coupling_weights/set_phase_biases (or their gripping counterparts) are called
+        let oscillator_coupling = PhaseCoupling::new(oscillators.len());
+        let gripping_oscillator_coupling = PhaseCoupling::new(gripping_oscillators.len());
+
+        let contact_normal_impulses = (0..somite_number).map(|_| cell::Cell::new(0.)).collect();
+        let ground_contact_forces = (0..somite_number).map(|_| cell::Cell::new(0.)).collect();
+
         Caterpillar::create_instance(
             py,
             config,
             somites,
             simulation_protocol,
             cell::Cell::<usize>::new(0),
+            cell::Cell::new(0.),
             temp_forces,
             oscillators,
             oscillator_ids,
@@ -244,11 +384,22 @@ py_class!(class Caterpillar |py| {
             gripping_thresholds,
             previous_vertical_torsion_spring_angles,
             cell::Cell::new(0.0),
+            cell::Cell::new(Coordinate::new(0., 0., -1.)),
             dy,
             path_heights,
             somite_distances,
             somite_angles,
             cell::RefCell::new(TimeProfiler::new()),
+            integrator,
+            cell::RefCell::new(Vec::new()),
+            oscillator_coupling,
+            gripping_oscillator_coupling,
+            contact_model,
+            contact_normal_impulses,
+            cell::RefCell::new(StepLogger::new()),
+            cell::RefCell::new(MeshTerrain::new()),
+            ground_contact_forces,
+            cell::Cell::new((Coordinate::zero(), Coordinate::zero())),
         )
     }
     def print_config(&self) -> PyResult<PyString> {
@@ -286,11 +437,152 @@ py_class!(class Caterpillar |py| {
         self.simulation_protocol(py).save(&file_path);
         Ok(py.None())
     }
+    /// Write the recorded simulation out as an HDF5 file (positions, radii and
+    /// gripping state per frame, plus dt/somite_count/gravity_angle/somite_radius
+    /// metadata), for interchange with pandas/h5py instead of the custom JSON format.
+    def save_simulation_hdf5(&self, file_path: String) -> PyResult<PyObject> {
+        let simulation_protocol = self.simulation_protocol(py);
+        match hdf5_export::save_simulation_hdf5(
+            &file_path,
+            simulation_protocol.objects(),
+            &simulation_protocol.frames(),
+            self.config(py),
+            self.gravity_angle(py).get(),
+        ) {
+            Ok(_) => Ok(py.None()),
+            Err(e) => Err(PyErr::new::<PyString, _>(py, &format!("failed to write hdf5 simulation to {}: {}", file_path, e))),
+        }
+    }
     def set_force_on_somite(&self, somite_number: usize, force: (f64, f64, f64)) -> PyResult<PyObject> {
         self.temp_forces(py)[somite_number].set(
             self.temp_forces(py)[somite_number].get() + Coordinate::from_tuple(force));
         Ok(py.None())
     }
+    /// Register `callback` to be invoked from update_state every `period` frames
+    /// (i.e. whenever frame_count % period == 0), with the current center of mass and
+    /// per-somite positions/velocities, so callers can run closed-loop control or
+    /// logging at a fixed substep rate even when stepping in large Rust-side batches
+    /// (e.g. steps_with_feedbacks) rather than one Python step() call at a time.
+    def register_periodic_callback(&self, period: usize, callback: PyObject) -> PyResult<PyObject> {
+        self.periodic_callbacks(py).borrow_mut().push((period, callback));
+        Ok(py.None())
+    }
+    /// Record `columns` (names drawn from get_somite_distances/get_somite_angles/
+    /// somite_phases/gripper_phases/gripping_force_x/tensions) into in-memory buffers
+    /// every `every_n` steps of the steps_with_feedbacks loop, entirely Rust-side, so
+    /// dense per-step traces don't cost a Python round-trip per step. Call flush_log
+    /// to write the buffered rows out as CSV.
+    def enable_logging(&self, columns: PyTuple, every_n: usize) -> PyResult<PyObject> {
+        let mut parsed_columns = Vec::<LogColumn>::with_capacity(columns.len(py));
+        for c in columns.iter(py) {
+            match LogColumn::from_name(&c.extract::<String>(py).unwrap()) {
+                Ok(column) => parsed_columns.push(column),
+                Err(message) => return Err(PyErr::new::<PyString, _>(py, &message)),
+            }
+        }
+        let widths = parsed_columns.iter().map(|&c| self.log_column_values(py, c).len()).collect::<Vec<usize>>();
+        let mut logger = self.step_logger(py).borrow_mut();
+        logger.enable(parsed_columns, widths);
+        logger.set_sample_interval(every_n);
+        Ok(py.None())
+    }
+    /// Write every row buffered by enable_logging out to `file_path` as CSV, with a
+    /// header naming each flattened field.
+    def flush_log(&self, file_path: String) -> PyResult<PyObject> {
+        match self.step_logger(py).borrow().flush(&file_path) {
+            Ok(_) => Ok(py.None()),
+            Err(e) => Err(PyErr::new::<PyString, _>(py, &format!("failed to flush log to {}: {}", file_path, e))),
+        }
+    }
+    /// Restore the body to its just-constructed "still somite" layout and clear all
+    /// per-step bookkeeping, so an RL training loop can start a fresh episode without
+    /// throwing away and re-parsing config/path heights into a brand new Caterpillar.
+    /// Oscillator phases are left untouched; call reset_oscillator_phases afterward to
+    /// seed them too.
+    def reset(&self) -> PyResult<PyObject> {
+        let config = self.config(py);
+        for (i, s) in self.somites(py).iter().enumerate() {
+            s.set_position(Coordinate { x: (i as f64) * 2. * config.somite_radius, y: 0., z: config.somite_radius });
+            s.set_verocity(Coordinate::zero());
+            s.set_force(Coordinate::zero());
+            s.set_orientation(Quaternion::identity());
+            s.set_angular_velocity(Coordinate::zero());
+            s.set_torque(Coordinate::zero());
+            s.release();
+            s.unstick();
+        }
+
+        for f in self.temp_forces(py) {
+            f.set(Coordinate::zero());
+        }
+        for f in self.gripping_forces(py) {
+            f.set(Coordinate::zero());
+        }
+        for tension in self.realtime_tunable_torsion_spring_tensions(py) {
+            tension.set(0.);
+        }
+        for angle in self.previous_vertical_torsion_spring_angles(py) {
+            angle.set(0.);
+        }
+        for distance in self.somite_distances(py) {
+            distance.set(config.somite_radius * 2.0);
+        }
+        for angle in self.somite_angles(py) {
+            angle.set(0.0);
+        }
+        self.target_angles(py).borrow_mut().clear();
+        for impulse in self.contact_normal_impulses(py) {
+            impulse.set(0.);
+        }
+        for force in self.ground_contact_forces(py) {
+            force.set(0.);
+        }
+        self.grasp_wrench(py).set((Coordinate::zero(), Coordinate::zero()));
+
+        self.simulation_protocol(py).clear();
+        self.frame_count(py).set(0);
+        self.simulation_time(py).set(0.);
+
+        Ok(py.None())
+    }
+    /// Seed oscillator phases deterministically (e.g. for a reproducible episode start)
+    /// instead of leaving them wherever the previous episode left off.
+    def reset_oscillator_phases(&self, phases_somites: PyTuple, phases_grippers: PyTuple) -> PyResult<PyObject> {
+        if phases_somites.len(py) != self.oscillators(py).len() {
+            panic!("number of elements in phases_somites({}) and oscillator controllers({}) are inconsistent",
+                phases_somites.len(py), self.oscillators(py).len());
+        }
+        if phases_grippers.len(py) != self.gripping_oscillators(py).len() {
+            panic!("number of elements in phases_grippers({}) and gripping oscillator controllers({}) are inconsistent",
+                phases_grippers.len(py), self.gripping_oscillators(py).len());
+        }
+        for (o, phase) in self.oscillators(py).iter().zip(phases_somites.iter(py)) {
+            o.set_phase(phase.extract::<f64>(py).unwrap());
+        }
+        for (o, phase) in self.gripping_oscillators(py).iter().zip(phases_grippers.iter(py)) {
+            o.set_phase(phase.extract::<f64>(py).unwrap());
+        }
+        Ok(py.None())
+    }
+    /// Somite distances, somite angles, somite phases, gripper phases, gripping forces
+    /// (x component; ground friction is not included) and actuator tensions in one
+    /// tuple, so an RL agent can read
+    /// a full observation with a single FFI call instead of six.
+    def observation(&self) -> PyResult<PyTuple> {
+        Ok(
+            PyTuple::new(
+                py,
+                &[
+                    try!(self.get_somite_distances(py)).into_object(),
+                    try!(self.get_somite_angles(py)).into_object(),
+                    try!(self.somite_phases(py)).into_object(),
+                    try!(self.gripper_phases(py)).into_object(),
+                    try!(self.gripping_force_x(py)).into_object(),
+                    try!(self.tensions(py)).into_object(),
+                ],
+            )
+        )
+    }
     def set_oscillation_ranges(&self, angle_ranges: PyTuple) -> PyResult<PyObject> {
         if angle_ranges.len(py) != self.oscillators(py).len() {
             panic!("number of elements in angle_ranges({}) and oscillator controllers({}) are inconsistent",
@@ -315,6 +607,31 @@ py_class!(class Caterpillar |py| {
         }
         Ok(py.None())
     }
+    /// Coupling weights `w[i][j]` between body actuators, indexed by position in
+    /// `oscillators` (not somite id). `weights` is a tuple of `n` tuples of `n` floats,
+    /// where `n == oscillators.len()`; `w[i][j]` pulls oscillator `i` toward oscillator
+    /// `j`'s phase (offset by the matching `set_phase_biases` entry) each step.
+    def set_coupling_weights(&self, weights: PyTuple) -> PyResult<PyObject> {
+        self.oscillator_coupling(py).set_weights(Self::matrix_from_py_tuple(py, weights, self.oscillators(py).len()));
+        Ok(py.None())
+    }
+    /// Desired phase biases `psi[i][j]` paired with `set_coupling_weights`; see there
+    /// for the shape and indexing convention.
+    def set_phase_biases(&self, phase_biases: PyTuple) -> PyResult<PyObject> {
+        self.oscillator_coupling(py).set_phase_biases(Self::matrix_from_py_tuple(py, phase_biases, self.oscillators(py).len()));
+        Ok(py.None())
+    }
+    /// Coupling weights between grippers; see set_coupling_weights for the shape and
+    /// indexing convention (here indexed by position in gripping_oscillators).
+    def set_gripping_coupling_weights(&self, weights: PyTuple) -> PyResult<PyObject> {
+        self.gripping_oscillator_coupling(py).set_weights(Self::matrix_from_py_tuple(py, weights, self.gripping_oscillators(py).len()));
+        Ok(py.None())
+    }
+    /// Desired phase biases paired with set_gripping_coupling_weights.
+    def set_gripping_phase_biases(&self, phase_biases: PyTuple) -> PyResult<PyObject> {
+        self.gripping_oscillator_coupling(py).set_phase_biases(Self::matrix_from_py_tuple(py, phase_biases, self.gripping_oscillators(py).len()));
+        Ok(py.None())
+    }
     def set_target_angle(&self, target_somite_id: usize, target_angle: f64) -> PyResult<PyObject> {
         match self.oscillator_ids(py).binary_search(&target_somite_id) {
             Ok(_) => {
@@ -324,14 +641,41 @@ py_class!(class Caterpillar |py| {
             Err(_) => Err(PyErr::new::<PyString, _>(py, &format!("segment with id {} does not hold an oscillator", target_somite_id))),
         }
     }
+    /// Drive the goal spring-damper (see add_inner_spring_forces/config's
+    /// goal_spring_k/goal_spring_c) on somite `somite_id` toward `(x, y, z)`; driving
+    /// this over time is how actuation such as peristalsis is expressed.
+    def set_goal_position(&self, somite_id: usize, x: f64, y: f64, z: f64) -> PyResult<PyObject> {
+        match self.somites(py).get(somite_id) {
+            Some(somite) => {
+                somite.set_goal_position(Coordinate::new(x, y, z));
+                Ok(py.None())
+            },
+            None => Err(PyErr::new::<PyString, _>(py, &format!("no somite with id {}", somite_id))),
+        }
+    }
+    /// Stop actuating somite `somite_id`'s goal spring until set_goal_position is
+    /// called on it again.
+    def clear_goal_position(&self, somite_id: usize) -> PyResult<PyObject> {
+        match self.somites(py).get(somite_id) {
+            Some(somite) => {
+                somite.clear_goal();
+                Ok(py.None())
+            },
+            None => Err(PyErr::new::<PyString, _>(py, &format!("no somite with id {}", somite_id))),
+        }
+    }
     def step(&self, dt: f64) -> PyResult<PyObject> {
         // update somites' oscillators
-        for oscillator in self.oscillators(py) {
-            oscillator.step(self.config(py).normal_angular_velocity, dt);
+        let phases = self.oscillators(py).iter().map(|o| o.get_phase()).collect::<Vec<f64>>();
+        for (i, oscillator) in self.oscillators(py).iter().enumerate() {
+            let phase_speed = self.config(py).normal_angular_velocity + self.oscillator_coupling(py).coupling_term(i, &phases);
+            oscillator.step(phase_speed, dt);
         }
         // update grippers' oscillators
-        for oscillator in self.gripping_oscillators(py) {
-            oscillator.step(self.config(py).normal_angular_velocity, dt);
+        let gripping_phases = self.gripping_oscillators(py).iter().map(|o| o.get_phase()).collect::<Vec<f64>>();
+        for (i, oscillator) in self.gripping_oscillators(py).iter().enumerate() {
+            let phase_speed = self.config(py).normal_angular_velocity + self.gripping_oscillator_coupling(py).coupling_term(i, &gripping_phases);
+            oscillator.step(phase_speed, dt);
         }
         self.update_state(py, dt);
         Ok(py.None())
@@ -344,15 +688,24 @@ py_class!(class Caterpillar |py| {
         if feedbacks_grippers.len(py) != self.gripping_oscillators(py).len() {
             panic!("number of elements in feedbacks_grippers and oscillator controllers for grippers are inconsistent");
         }
-        // update phase oscillators for somite actuators
+        // update phase oscillators for somite actuators; phases are snapshotted before any
+        // oscillator steps so the coupling term is simultaneous/explicit
+        let phases = self.oscillators(py).iter().map(|o| o.get_phase()).collect::<Vec<f64>>();
         for (i, f) in feedbacks_somites.iter(py).enumerate() {
-            self.oscillators(py)[i].step(self.config(py).normal_angular_velocity + f.extract::<f64>(py).unwrap(), dt);
+            let phase_speed = self.config(py).normal_angular_velocity
+                + f.extract::<f64>(py).unwrap()
+                + self.oscillator_coupling(py).coupling_term(i, &phases);
+            self.oscillators(py)[i].step(phase_speed, dt);
         }
         // self.profiler(py).borrow_mut().check("updating oscillators for segments");
 
         // update phase oscillators for grippers
-        for (f, o) in feedbacks_grippers.iter(py).zip(self.gripping_oscillators(py).iter()) {
-            o.step(self.config(py).normal_angular_velocity + f.extract::<f64>(py).unwrap(), dt);
+        let gripping_phases = self.gripping_oscillators(py).iter().map(|o| o.get_phase()).collect::<Vec<f64>>();
+        for (i, (f, o)) in feedbacks_grippers.iter(py).zip(self.gripping_oscillators(py).iter()).enumerate() {
+            let phase_speed = self.config(py).normal_angular_velocity
+                + f.extract::<f64>(py).unwrap()
+                + self.gripping_oscillator_coupling(py).coupling_term(i, &gripping_phases);
+            o.step(phase_speed, dt);
         }
         // self.profiler(py).borrow_mut().check("updating oscillators for grippers");
 
@@ -377,15 +730,31 @@ py_class!(class Caterpillar |py| {
             panic!("number of elements in feedbacks_grippers and oscillator controllers for grippers are inconsistent");
         }
         
-        for _ in 0..steps { // run for several steps
+        for step in 0..steps { // run for several steps
+            let phases = self.oscillators(py).iter().map(|o| o.get_phase()).collect::<Vec<f64>>();
             for (i, f) in feedbacks_somites.iter(py).enumerate() { // update phase oscillators for somite actuators
-                self.oscillators(py)[i].step(self.config(py).normal_angular_velocity + f.extract::<f64>(py).unwrap(), dt);
+                let phase_speed = self.config(py).normal_angular_velocity
+                    + f.extract::<f64>(py).unwrap()
+                    + self.oscillator_coupling(py).coupling_term(i, &phases);
+                self.oscillators(py)[i].step(phase_speed, dt);
             }
             // update phase oscillators for grippers
-            for (f, o) in feedbacks_grippers.iter(py).zip(self.gripping_oscillators(py).iter()) {
-                o.step(self.config(py).normal_angular_velocity + f.extract::<f64>(py).unwrap(), dt);
+            let gripping_phases = self.gripping_oscillators(py).iter().map(|o| o.get_phase()).collect::<Vec<f64>>();
+            for (i, (f, o)) in feedbacks_grippers.iter(py).zip(self.gripping_oscillators(py).iter()).enumerate() {
+                let phase_speed = self.config(py).normal_angular_velocity
+                    + f.extract::<f64>(py).unwrap()
+                    + self.gripping_oscillator_coupling(py).coupling_term(i, &gripping_phases);
+                o.step(phase_speed, dt);
             }
             self.update_state(py, dt);
+
+            // sample the configured log columns every step_logger.every_n steps, entirely
+            // Rust-side (no per-step round-trip back to Python)
+            if self.step_logger(py).borrow().should_sample(step as usize) {
+                let columns = self.step_logger(py).borrow().columns().iter().map(|&(c, _)| c).collect::<Vec<LogColumn>>();
+                let row = columns.iter().flat_map(|&c| self.log_column_values(py, c)).collect::<Vec<f64>>();
+                self.step_logger(py).borrow_mut().record(row);
+            }
         }
         self.profiler(py).borrow_mut().save(&*(profile_save_file.to_string_lossy(py)));
         Ok(py.None())
@@ -436,6 +805,53 @@ py_class!(class Caterpillar |py| {
             )
         )
     }
+    /// Per-somite normal impulse from the most recent complementarity contact
+    /// resolution (0 for somites not in contact). Stays all-zero under the default
+    /// Penalty contact model; only populated when constructed with
+    /// `contact_model="complementarity"`.
+    def contact_forces(&self) -> PyResult<PyTuple> {
+        Ok(
+            PyTuple::new(
+                py,
+                self.contact_normal_impulses(py).iter().map(|impulse| {
+                    impulse.get().into_py_object(py).into_object()
+                }).collect::<Vec<PyObject>>().as_slice(),
+            )
+        )
+    }
+    /// Per-somite vertical ground contact force from the compliant penalty model
+    /// (see `mask_force_on_landing`); 0 under the hard-clamp fallback (`contact_k`
+    /// unset) or while a somite isn't touching the ground.
+    def contact_force_z(&self) -> PyResult<PyTuple> {
+        Ok(
+            PyTuple::new(
+                py,
+                self.ground_contact_forces(py).iter().map(|f| {
+                    f.get().into_py_object(py).into_object()
+                }).collect::<Vec<PyObject>>().as_slice(),
+            )
+        )
+    }
+    /// Net (force, torque) the current step's gripping and ground-contact wrenches
+    /// exert about `calculate_center_of_mass`, expressed in the body frame built by
+    /// `body_orientation` so a controller can read net propulsive force and yaw/pitch
+    /// torque without re-deriving the body's attitude itself. Zero wrench and zero
+    /// torque while no somite is gripping or touching the ground.
+    def resultant_wrench(&self) -> PyResult<PyTuple> {
+        let (force, torque) = self.grasp_wrench(py).get();
+        Ok(PyTuple::new(py, &[
+            PyTuple::new(py, &[
+                force.x.into_py_object(py).into_object(),
+                force.y.into_py_object(py).into_object(),
+                force.z.into_py_object(py).into_object(),
+            ]).into_object(),
+            PyTuple::new(py, &[
+                torque.x.into_py_object(py).into_object(),
+                torque.y.into_py_object(py).into_object(),
+                torque.z.into_py_object(py).into_object(),
+            ]).into_object(),
+        ]))
+    }
     def somite_phases(&self) -> PyResult<PyTuple> {
         Ok(
             PyTuple::new(py, self.oscillators(py).iter().map(|o| {
@@ -452,10 +868,43 @@ py_class!(class Caterpillar |py| {
     }
     def set_gravity_angle(&self, new_angle: f64) -> PyResult<PyObject>{
         self.gravity_angle(py).set(new_angle);
+        self.gravity_vector(py).set(Coordinate::new(-new_angle.sin(), 0., -new_angle.cos()));
         Ok(py.None())
     }
+    /// Set gravity's direction from an arbitrary 3D vector instead of the single
+    /// planar angle `set_gravity_angle` accepts, so dynamics can project
+    /// GRAVITATIONAL_ACCELERATION onto inclined/banked terrain rather than only a
+    /// tilt within the x-z plane. `(x, y, z)` only needs to point the right way; it is
+    /// normalized before being stored. Does not update `gravity_angle`, since a
+    /// general 3D direction has no unique planar angle to report back.
+    def set_gravity_vector(&self, x: f64, y: f64, z: f64) -> PyResult<PyObject> {
+        let direction = Coordinate::new(x, y, z);
+        let norm = direction.norm();
+        if norm == 0. {
+            return Err(PyErr::new::<PyString, _>(py, &"gravity vector must be nonzero".to_string()));
+        }
+        self.gravity_vector(py).set(direction / norm);
+        Ok(py.None())
+    }
+    /// Fit a body frame to the somite chain (tail-to-head axis as forward, the world
+    /// vertical as up; the terrain slope from `path_heights` is not taken into
+    /// account) and report its attitude as roll/pitch/yaw in radians.
+    def body_orientation(&self) -> PyResult<PyTuple> {
+        let somites = self.somites(py);
+        let tail = somites.first().unwrap().get_position();
+        let head = somites.last().unwrap().get_position();
+        let forward = head - tail;
+        let up = Coordinate::new(0., 0., 1.);
+        let (roll, pitch, yaw) = Quaternion::from_basis(forward, up).to_euler();
+        Ok(PyTuple::new(py, &[
+            roll.into_py_object(py).into_object(),
+            pitch.into_py_object(py).into_object(),
+            yaw.into_py_object(py).into_object(),
+        ]))
+    }
     def is_on_ground(&self) -> PyResult<bool> {
-        Ok(self.somites(py).iter().fold(false, |acc, ref s| acc ||  self.path_heights(py).is_on_ground(s)))
+        let t = self.simulation_time(py).get();
+        Ok(self.somites(py).iter().fold(false, |acc, ref s| acc ||  self.path_heights(py).is_on_ground(s, t)))
     }
     def set_gripper_phase(&self, somite_id: usize, phase: f64) -> PyResult<PyObject> {
         // set phase of gripper oscillator on soimte designated by somite_id
@@ -469,7 +918,21 @@ py_class!(class Caterpillar |py| {
     }
     def is_head_blocked(&self) -> PyResult<bool> {
         // return true if head is blocked by an obstacle and cannot move forward anymore
-        Ok(self.dynamics(py).is_blocked_by_obstacle(self.somites(py).last().unwrap(), self.path_heights(py)))
+        Ok(self.dynamics(py).is_blocked_by_obstacle(self.somites(py).last().unwrap(), self.path_heights(py), self.simulation_time(py).get()))
+    }
+    /// Add a triangle to the mesh terrain backend; once any triangle is loaded,
+    /// obstacle contact for every somite is resolved via segment-triangle
+    /// intersection against the whole mesh instead of the 1-D `path_heights`
+    /// profile (see `clamp_to_obstacle`).
+    def add_terrain_triangle(
+        &self, ax: f64, ay: f64, az: f64, bx: f64, by: f64, bz: f64, cx: f64, cy: f64, cz: f64
+    ) -> PyResult<PyObject> {
+        self.mesh_terrain(py).borrow_mut().add_triangle(
+            Coordinate::new(ax, ay, az),
+            Coordinate::new(bx, by, bz),
+            Coordinate::new(cx, cy, cz),
+        );
+        Ok(py.None())
     }
     def get_somite_distances(&self) -> PyResult<PyTuple> {
         // distance between i-th and (i+1)-th somite is saved in the i-th element of self.somite_distances(py)
@@ -512,6 +975,35 @@ impl Caterpillar {
         path_heights
     }
 
+    /// Parse a tuple-of-tuples of floats into a square `size x size` matrix, as used by
+    /// set_coupling_weights/set_phase_biases and their gripping counterparts.
+    fn matrix_from_py_tuple(py: Python, rows: PyTuple, size: usize) -> Vec<Vec<f64>> {
+        if rows.len(py) != size {
+            panic!("number of rows ({}) does not match number of oscillators ({})", rows.len(py), size);
+        }
+        rows.iter(py).map(|row| {
+            let row = row.extract::<PyTuple>(py).unwrap();
+            if row.len(py) != size {
+                panic!("number of columns ({}) does not match number of oscillators ({})", row.len(py), size);
+            }
+            row.iter(py).map(|v| v.extract::<f64>(py).unwrap()).collect()
+        }).collect()
+    }
+
+    /// Current value of a loggable quantity, flattened to one `f64` per element
+    /// (e.g. one per somite or per actuator), matching the Python-facing accessor of
+    /// the same name.
+    fn log_column_values(&self, py: Python, column: LogColumn) -> Vec<f64> {
+        match column {
+            LogColumn::SomiteDistances => self.somite_distances(py).iter().map(|c| c.get()).collect(),
+            LogColumn::SomiteAngles => self.somite_angles(py).iter().map(|c| c.get()).collect(),
+            LogColumn::SomitePhases => self.oscillators(py).iter().map(|o| o.get_phase()).collect(),
+            LogColumn::GripperPhases => self.gripping_oscillators(py).iter().map(|o| o.get_phase()).collect(),
+            LogColumn::GrippingForceX => self.gripping_forces(py).iter().map(|f| f.get().x).collect(),
+            LogColumn::Tensions => self.realtime_tunable_torsion_spring_tensions(py).iter().map(|t| t.get()).collect(),
+        }
+    }
+
     fn calculate_center_of_mass(&self, py: Python) -> Coordinate {
         let mut sum = Coordinate::new(0., 0., 0.);
         for s in self.somites(py) {
@@ -522,18 +1014,10 @@ impl Caterpillar {
 
     fn update_state(&self, py: Python, time_delta: f64) {
         // self.profiler(py).borrow_mut().check("start updating state");
-        
-        self.update_somite_positions(py, time_delta);
-        // self.profiler(py).borrow_mut().check("update segment positions");
-        
-        let new_forces = self.calculate_force_on_somites(py, time_delta);
-        // self.profiler(py).borrow_mut().check("calculate forces");
 
-        self.update_somite_verocities(py, time_delta, &new_forces);
-        // self.profiler(py).borrow_mut().check("update velocities");
-
-        self.update_somite_forces(py, &new_forces);
-        // self.profiler(py).borrow_mut().check("update forces");
+        self.simulation_time(py).set(self.simulation_time(py).get() + time_delta);
+        self.step_with_ground_contact(py, time_delta, GROUND_CONTACT_SUBSTEP_DEPTH);
+        // self.profiler(py).borrow_mut().check("update segment positions, velocities and forces");
 
         // save simulation result
         let decimation_span = 10_usize;
@@ -546,22 +1030,405 @@ impl Caterpillar {
         }
         // self.profiler(py).borrow_mut().check("save simulation results");
 
+        self.run_periodic_callbacks(py);
+
         self.frame_count(py).set(self.frame_count(py).get() + 1);
         // self.profiler(py).borrow_mut().check("set frame count");
     }
 
+    /// Fire every registered periodic callback whose period evenly divides frame_count.
+    fn run_periodic_callbacks(&self, py: Python) {
+        if self.periodic_callbacks(py).borrow().is_empty() {
+            return;
+        }
+
+        let frame_count = self.frame_count(py).get();
+        let center_of_mass = self.calculate_center_of_mass(py).to_tuple().into_py_object(py).into_object();
+        let positions = PyTuple::new(
+            py,
+            self.somites(py).iter().map(|s| s.get_position().to_tuple().into_py_object(py).into_object()).collect::<Vec<PyObject>>().as_slice(),
+        ).into_object();
+        let verocities = PyTuple::new(
+            py,
+            self.somites(py).iter().map(|s| s.get_verocity().to_tuple().into_py_object(py).into_object()).collect::<Vec<PyObject>>().as_slice(),
+        ).into_object();
+
+        for &(period, ref callback) in self.periodic_callbacks(py).borrow().iter() {
+            if period != 0 && frame_count % period == 0 {
+                callback.call(py, (center_of_mass.clone_ref(py), positions.clone_ref(py), verocities.clone_ref(py)), None).unwrap();
+            }
+        }
+    }
+
     fn build_current_frame(&self, py: Python) -> Vec<simulation_export::ObjectPosition> {
+        let tensions = self.realtime_tunable_torsion_spring_tensions(py);
         self.somites(py)
             .into_iter()
             .enumerate()
-            .map(|(i, s)| simulation_export::ObjectPosition {
-                id: format!("_somite_{}", i),
-                pos: s.get_position().to_tuple(),
-                gripping: s.is_gripping(),
+            .map(|(i, s)| {
+                let phase = self.oscillator_ids(py).binary_search(&i).ok()
+                    .map(|osc_index| self.oscillators(py)[osc_index].get_phase());
+                let tension = if i >= 1 && i <= tensions.len() {
+                    Some(tensions[i - 1].get())
+                } else {
+                    None
+                };
+                simulation_export::ObjectPosition {
+                    id: format!("_somite_{}", i),
+                    pos: s.get_position().to_tuple(),
+                    orientation: Some(s.get_orientation().to_tuple()),
+                    verocity: Some(s.get_verocity().to_tuple()),
+                    gripping: s.is_gripping(),
+                    phase: phase,
+                    tension: tension,
+                }
             })
             .collect()
     }
 
+    /// At coarse `time_delta`, a fast-falling somite's bottom can pass clean through
+    /// the ground plane within a single step, before `mask_force_on_landing`'s
+    /// after-the-fact clamp ever sees it. Detect that crossing ahead of time and
+    /// bisect the step at the contact instant, so the landing clamp is applied right
+    /// where the somite actually reaches the ground instead of one step too late.
+    /// Recursion is bounded by `depth` since each bisection only resolves the
+    /// earliest crossing and a somite can still be falling after it lands.
+    fn step_with_ground_contact(&self, py: Python, time_delta: f64, depth: usize) {
+        match self.ground_crossing_time(py, time_delta) {
+            Some(crossing) if depth > 0 => {
+                self.step_one(py, crossing);
+                self.step_with_ground_contact(py, time_delta - crossing, depth - 1);
+            }
+            _ => self.step_one(py, time_delta),
+        }
+    }
+
+    fn step_one(&self, py: Python, time_delta: f64) {
+        match *self.integrator(py) {
+            Integrator::Verlet => self.step_verlet(py, time_delta),
+            Integrator::SemiImplicitEuler { iterations, tolerance } => {
+                self.step_semi_implicit_euler(py, time_delta, iterations, tolerance)
+            }
+            Integrator::Rk4 => self.step_rk4(py, time_delta),
+            Integrator::Xpbd { substeps, iterations } => self.step_xpbd(py, time_delta, substeps, iterations),
+        }
+        if let ContactModel::Complementarity { restitution, max_iterations, tolerance } = *self.contact_model(py) {
+            let impulses = self.dynamics(py).resolve_complementarity_contacts(
+                self.somites(py), self.path_heights(py), self.simulation_time(py).get(), restitution, max_iterations, tolerance,
+            );
+            for (cell, impulse) in self.contact_normal_impulses(py).iter().zip(impulses.iter()) {
+                cell.set(*impulse);
+            }
+        }
+    }
+
+    /// Time within `[0, time_delta]` at which the first somite whose bottom
+    /// (`z - radius`) is still above its local ground height would cross it,
+    /// assuming each somite keeps its current `z`-velocity over the step; `None` if
+    /// no somite is on a tunneling trajectory.
+    fn ground_crossing_time(&self, py: Python, time_delta: f64) -> Option<f64> {
+        let path_heights = self.path_heights(py);
+        let t = self.simulation_time(py).get();
+        self.somites(py)
+            .iter()
+            .filter_map(|s| {
+                let ground = path_heights.get_height(s.get_position().x, t);
+                let bottom = s.get_position().z - s.radius;
+                let velocity_z = s.get_verocity().z;
+                if bottom > ground && velocity_z < 0. && bottom + velocity_z * time_delta <= ground {
+                    Some((bottom - ground) / -velocity_z)
+                } else {
+                    None
+                }
+            })
+            .fold(None, |earliest: Option<f64>, t| match earliest {
+                Some(e) if e <= t => Some(e),
+                _ => Some(t),
+            })
+    }
+
+    /// Advance one step with explicit velocity Verlet (the long-standing default).
+    fn step_verlet(&self, py: Python, time_delta: f64) {
+        self.update_somite_positions(py, time_delta);
+        self.update_somite_orientations(py, time_delta);
+        let (new_forces, new_torques) = self.calculate_force_on_somites(py, time_delta);
+        self.update_somite_verocities(py, time_delta, &new_forces);
+        self.update_somite_angular_velocities(py, time_delta, &new_torques);
+        self.update_somite_forces(py, &new_forces);
+        self.update_somite_torques(py, &new_torques);
+    }
+
+    /// Advance one step with semi-implicit (backward) Euler, solved by fixed-point
+    /// iteration: start from the explicit Verlet guess for `x_{t+1}`, then repeatedly
+    /// re-evaluate `F(x_{t+1})` and refine `v_{t+1}`/`x_{t+1}` (re-applying the same
+    /// gripping/obstacle/ground clamps `update_somite_positions`/`update_somite_verocities`
+    /// apply) until the largest position change across somites falls below `tolerance`
+    /// or `iterations` is exhausted. Stable at much larger `time_delta` than
+    /// `step_verlet` for the stiff springs in `CONFIG`. `temp_forces` is drained once
+    /// and grip state toggled once, both after the Verlet guess has moved the somites
+    /// and before the refinement loop, rather than re-triggering on every
+    /// refinement's force evaluation.
+    fn step_semi_implicit_euler(&self, py: Python, time_delta: f64, iterations: usize, tolerance: f64) {
+        let x_t = self.somites(py).iter().map(|s| s.get_position()).collect::<Vec<Coordinate>>();
+        let v_t = self.somites(py).iter().map(|s| s.get_verocity()).collect::<Vec<Coordinate>>();
+
+        // initial guess for x_{t+1}: the explicit Verlet position update
+        self.update_somite_positions(py, time_delta);
+
+        let external_forces = self.drain_temp_forces(py);
+        self.update_grippers(py);
+
+        let (mut new_forces, mut new_torques) = self.evaluate_forces(py, time_delta, &external_forces);
+        for _ in 0..iterations {
+            let mut max_change = 0.0_f64;
+            for (i, s) in self.somites(py).iter().enumerate() {
+                let mut new_verocity = v_t[i] + new_forces[i] * time_delta / s.mass;
+                if s.is_gripping() {
+                    new_verocity.z = 0.; // cannot move if gripping
+                } else if self.path_heights(py).is_on_ground(s, self.simulation_time(py).get()) {
+                    new_verocity.z = new_verocity.z.max(0.);
+                }
+
+                let mut new_position = x_t[i] + new_verocity * time_delta;
+                if s.is_gripping() {
+                    // cannot move along the z-axis if gripping
+                    new_position.z = x_t[i].z;
+                }
+                new_position = self.clamp_to_obstacle(py, s, x_t[i], new_position);
+
+                max_change = max_change.max((new_position - s.get_position()).norm());
+                s.set_verocity(new_verocity);
+                s.set_position(new_position);
+            }
+
+            if max_change < tolerance {
+                break;
+            }
+            let (new_forces_, new_torques_) = self.evaluate_forces(py, time_delta, &external_forces);
+            new_forces = new_forces_;
+            new_torques = new_torques_;
+        }
+
+        // grasp/contact wrench bookkeeping reads the gripping and ground-contact
+        // forces the last evaluate_forces call finalized at the converged state
+        self.accumulate_resultant_wrench(py);
+
+        // save inter somite distances, same as update_somite_positions
+        for i in 0..self.somites(py).len() - 1 {
+            self.somite_distances(py)[i].set((self.somites(py)[i].get_position() - self.somites(py)[i + 1].get_position()).norm());
+        }
+
+        // rotational dynamics aren't part of the implicit solve above (only the stiff
+        // translational springs motivate it), so advance orientation explicitly with
+        // the converged torque, same as step_verlet would for a single iteration
+        self.update_somite_orientations(py, time_delta);
+        self.update_somite_angular_velocities(py, time_delta, &new_torques);
+        self.update_somite_forces(py, &new_forces);
+        self.update_somite_torques(py, &new_torques);
+    }
+
+    /// Advance one step with classical 4th-order Runge-Kutta on the translational
+    /// state `y = (position, velocity)`: evaluate the force field at `y_t`, two
+    /// midpoint estimates and `y_t + dt*k3`, then combine as
+    /// `y += dt/6 * (k1 + 2k2 + 2k3 + k4)`. Oscillator phases are held fixed across
+    /// the four sub-evaluations, advanced once per outer step as in the other
+    /// integrators. Rotational state is simply advanced once with the final (k4)
+    /// torque, as step_semi_implicit_euler already does for its own iteration.
+    /// `temp_forces` is drained once and grip state toggled once, both up front
+    /// against the true (undisplaced) state, and reused across all four stages so
+    /// external forces aren't consumed by k1 and grip toggling doesn't fire at the
+    /// displaced intermediate stage positions.
+    fn step_rk4(&self, py: Python, time_delta: f64) {
+        let x_t = self.somites(py).iter().map(|s| s.get_position()).collect::<Vec<Coordinate>>();
+        let v_t = self.somites(py).iter().map(|s| s.get_verocity()).collect::<Vec<Coordinate>>();
+
+        let external_forces = self.drain_temp_forces(py);
+        self.update_grippers(py);
+
+        // k1 is evaluated at y_t itself, i.e. no displacement (stage_dt=0 makes the
+        // displacement vectors passed in irrelevant)
+        let (k1_v, k1_f, _) = self.rk4_stage(py, &x_t, &v_t, 0., &v_t, &v_t, time_delta, &external_forces);
+        let (k2_v, k2_f, _) = self.rk4_stage(py, &x_t, &v_t, time_delta / 2., &k1_v, &k1_f, time_delta, &external_forces);
+        let (k3_v, k3_f, _) = self.rk4_stage(py, &x_t, &v_t, time_delta / 2., &k2_v, &k2_f, time_delta, &external_forces);
+        let (k4_v, k4_f, k4_torques) = self.rk4_stage(py, &x_t, &v_t, time_delta, &k3_v, &k3_f, time_delta, &external_forces);
+
+        for (i, s) in self.somites(py).iter().enumerate() {
+            let mut new_position = x_t[i] + (k1_v[i] + k2_v[i] * 2. + k3_v[i] * 2. + k4_v[i]) * (time_delta / 6.);
+            let mut new_verocity = v_t[i] + (k1_f[i] + k2_f[i] * 2. + k3_f[i] * 2. + k4_f[i]) * (time_delta / 6. / s.mass);
+            if s.is_gripping() {
+                new_verocity.z = 0.;
+                new_position.z = x_t[i].z;
+            } else if self.path_heights(py).is_on_ground(s, self.simulation_time(py).get()) {
+                new_verocity.z = new_verocity.z.max(0.);
+            }
+            new_position = self.clamp_to_obstacle(py, s, x_t[i], new_position);
+            s.set_position(new_position);
+            s.set_verocity(new_verocity);
+        }
+
+        // grasp/contact wrench bookkeeping reads the gripping and ground-contact
+        // forces the k4 evaluation finalized, now that somites sit at the true
+        // combined endpoint rather than the k4 stage's displaced position
+        self.accumulate_resultant_wrench(py);
+
+        for i in 0..self.somites(py).len() - 1 {
+            self.somite_distances(py)[i].set((self.somites(py)[i].get_position() - self.somites(py)[i + 1].get_position()).norm());
+        }
+
+        // rotational dynamics are advanced once with the endpoint (k4) torque, same
+        // simplification step_semi_implicit_euler makes for its own iteration
+        self.update_somite_orientations(py, time_delta);
+        self.update_somite_angular_velocities(py, time_delta, &k4_torques);
+        self.update_somite_forces(py, &k4_f);
+        self.update_somite_torques(py, &k4_torques);
+    }
+
+    /// Displace every somite to `x_t[i] + stage_v[i] * stage_dt`, `v_t[i] + stage_f[i]
+    /// / mass * stage_dt`, evaluate the force field there against the step's shared
+    /// `external_forces` snapshot, then return the velocities and forces at that
+    /// displaced state (the next `k`), plus torques for callers that need the
+    /// endpoint value. Leaves the somites at the displaced state, which the next
+    /// stage (or the caller's final combination) builds on or overwrites. Uses
+    /// `evaluate_forces` rather than `calculate_force_on_somites` so the temp-force
+    /// drain, gripper update, and wrench bookkeeping happen once per step in the
+    /// caller, not once per stage.
+    fn rk4_stage(
+        &self,
+        py: Python,
+        x_t: &Vec<Coordinate>,
+        v_t: &Vec<Coordinate>,
+        stage_dt: f64,
+        stage_v: &Vec<Coordinate>,
+        stage_f: &Vec<Coordinate>,
+        time_delta: f64,
+        external_forces: &Vec<Coordinate>,
+    ) -> (Vec<Coordinate>, Vec<Coordinate>, Vec<Coordinate>) {
+        for (i, s) in self.somites(py).iter().enumerate() {
+            s.set_position(x_t[i] + stage_v[i] * stage_dt);
+            s.set_verocity(v_t[i] + stage_f[i] * (stage_dt / s.mass));
+        }
+        let velocities = self.somites(py).iter().map(|s| s.get_verocity()).collect::<Vec<Coordinate>>();
+        let (forces, torques) = self.evaluate_forces(py, time_delta, external_forces);
+        (velocities, forces, torques)
+    }
+
+    /// Advance one step with Extended Position-Based Dynamics (XPBD): split
+    /// `time_delta` into `substeps` equal slices, and within each slice predict
+    /// positions under gravity alone, then resolve the inter-somite springs as
+    /// compliant distance constraints (compliance `alpha_tilde = (1/sp_k)/dt_sub^2`,
+    /// accumulating `lambda` per constraint across the sweep) together with
+    /// ground/gripping/obstacle position constraints over `iterations` Gauss-Seidel
+    /// sweeps, before recovering velocity from the position change. Stays stable at
+    /// much larger `sp_k` than `step_verlet`, since the spring no longer needs
+    /// explicit force integration. Rotational dynamics (torsion-spring torques,
+    /// orientation) are advanced once per outer step from the final position, the
+    /// same simplification `step_semi_implicit_euler` and `step_rk4` already make.
+    fn step_xpbd(&self, py: Python, time_delta: f64, substeps: usize, iterations: usize) {
+        let dt_sub = time_delta / substeps as f64;
+        let conf = self.config(py);
+        let compliance = (1. / conf.sp_k) / dt_sub.powi(2);
+        let gravity_acceleration = self.gravity_vector(py).get() * GRAVITATIONAL_ACCELERATION;
+
+        for _ in 0..substeps {
+            let x_prev = self.somites(py).iter().map(|s| s.get_position()).collect::<Vec<Coordinate>>();
+
+            // predict positions under gravity alone
+            for (i, s) in self.somites(py).iter().enumerate() {
+                s.set_position(x_prev[i] + s.get_verocity() * dt_sub + gravity_acceleration * (0.5 * dt_sub.powi(2)));
+            }
+
+            let mut lambdas = vec![0.; self.somites(py).len().saturating_sub(1)];
+            for _ in 0..iterations {
+                // inter-somite spring distance constraint
+                for i in 0..self.somites(py).len().saturating_sub(1) {
+                    let (a, b, w_a, w_b) = {
+                        let somites = self.somites(py);
+                        (somites[i].get_position(), somites[i + 1].get_position(), 1. / somites[i].mass, 1. / somites[i + 1].mass)
+                    };
+                    let delta = a - b;
+                    let distance = delta.norm();
+                    if distance < 1.0e-12 {
+                        continue;
+                    }
+                    let n = delta / distance;
+                    let c = distance - conf.sp_natural_length;
+                    let delta_lambda = (-c - compliance * lambdas[i]) / (w_a + w_b + compliance);
+                    lambdas[i] += delta_lambda;
+                    let somites = self.somites(py);
+                    somites[i].set_position(a + n * (w_a * delta_lambda));
+                    somites[i + 1].set_position(b - n * (w_b * delta_lambda));
+                }
+
+                // ground/gripping/obstacle position constraints, same semantics as the
+                // other integrators' explicit clamps
+                for (i, s) in self.somites(py).iter().enumerate() {
+                    let mut position = s.get_position();
+                    if s.is_gripping() {
+                        position.z = x_prev[i].z;
+                    } else if self.path_heights(py).is_on_ground(s, self.simulation_time(py).get()) {
+                        position.z = position.z.max(self.path_heights(py).get_height(position.x, self.simulation_time(py).get()) + s.radius);
+                    }
+                    position = self.clamp_to_obstacle(py, s, x_prev[i], position);
+                    s.set_position(position);
+                }
+            }
+
+            // recover velocity from the position change, then apply the same
+            // gripping/ground velocity clamps the other integrators apply
+            for (i, s) in self.somites(py).iter().enumerate() {
+                let mut new_verocity = (s.get_position() - x_prev[i]) / dt_sub;
+                if s.is_gripping() {
+                    new_verocity.z = 0.;
+                } else if self.path_heights(py).is_on_ground(s, self.simulation_time(py).get()) {
+                    new_verocity.z = new_verocity.z.max(0.);
+                }
+                s.set_verocity(new_verocity);
+            }
+        }
+
+        for i in 0..self.somites(py).len() - 1 {
+            self.somite_distances(py)[i].set((self.somites(py)[i].get_position() - self.somites(py)[i + 1].get_position()).norm());
+        }
+
+        let (new_forces, new_torques) = self.calculate_force_on_somites(py, time_delta);
+        self.update_somite_orientations(py, time_delta);
+        self.update_somite_angular_velocities(py, time_delta, &new_torques);
+        self.update_somite_forces(py, &new_forces);
+        self.update_somite_torques(py, &new_torques);
+    }
+
+    /// Resolve a somite's proposed move from `old_position` to `new_position` against
+    /// whichever obstacle backend is active: when `mesh_terrain` holds any triangles,
+    /// find the nearest segment-triangle hit and stop the motion there (pushing back
+    /// out along the hit's outward normal rather than only cancelling the x-axis
+    /// component), so slopes, overhangs and lateral walls all block correctly; when
+    /// the mesh is empty (the default), fall back to `path_heights::PathHeights::contact`'s
+    /// 1-D riser query, which only cancels the forward move once the somite is
+    /// actually within `radius` of the step boundary, rather than the old
+    /// `is_blocked_by_obstacle` check's bare height comparison with no regard for how
+    /// far past the boundary the somite was.
+    fn clamp_to_obstacle(&self, py: Python, s: &somite::Somite, old_position: Coordinate, new_position: Coordinate) -> Coordinate {
+        let mesh_terrain = self.mesh_terrain(py).borrow();
+        if !mesh_terrain.is_empty() {
+            if let Some(contact) = mesh_terrain.nearest_contact(old_position, new_position) {
+                let hit = old_position + (new_position - old_position) * contact.u;
+                let into_surface = (new_position - hit).inner_product(contact.normal);
+                if into_surface < 0. {
+                    return new_position - contact.normal * into_surface;
+                }
+            }
+            return new_position;
+        }
+
+        let mut clamped = new_position;
+        if let Some(contact) = self.path_heights(py).contact(s, self.simulation_time(py).get()) {
+            if contact.normal.x != 0. {
+                clamped.x = old_position.x.min(new_position.x); // if blocked, cancel the forward move
+            }
+        }
+        clamped
+    }
+
     fn update_somite_positions(&self, py: Python, time_delta: f64) {
         // update somite positions based on Velret's method
         // x_{t+1} = x_{t} + \delta t v_{t} + 0.5 \delta t^2 f_{t, x_t}
@@ -572,9 +1439,7 @@ impl Caterpillar {
                 // cannot move along the z-axis if gripping
                 new_position.z = s.get_position().z;
             }
-            if self.dynamics(py).is_blocked_by_obstacle(s, self.path_heights(py)) {
-                new_position.x = s.get_position().x.min(new_position.x); // if blocked, cancel the forward move
-            }
+            new_position = self.clamp_to_obstacle(py, s, s.get_position(), new_position);
             s.set_position(new_position);
 
         }
@@ -585,15 +1450,48 @@ impl Caterpillar {
         }
     }
 
+    /// Advance each somite's attitude quaternion by its current angular velocity:
+    /// dq/dt = 0.5 * omega_quat * q, where omega_quat is angular_velocity lifted to a
+    /// pure quaternion. Mirrors update_somite_positions's role for translational DOF.
+    fn update_somite_orientations(&self, py: Python, time_delta: f64) {
+        for s in self.somites(py) {
+            let omega = s.get_angular_velocity();
+            let omega_quat = Quaternion::new(0., omega.x, omega.y, omega.z);
+            let q = s.get_orientation();
+            let dq = (omega_quat * q) * 0.5 * time_delta;
+            s.set_orientation((q + dq).normalize());
+        }
+    }
+
+    /// omega_{t+1} = omega_t + \delta t (tau_t + tau_{t+1}) / (2 I), the rotational
+    /// analogue of update_somite_verocities.
+    fn update_somite_angular_velocities(&self, py: Python, time_delta: f64, new_torques: &Vec<Coordinate>) {
+        for (i, s) in self.somites(py).iter().enumerate() {
+            let new_angular_velocity = s.get_angular_velocity()
+                + (s.get_torque() + new_torques[i]) * 0.5 * time_delta / s.moment_of_inertia;
+            s.set_angular_velocity(new_angular_velocity);
+        }
+    }
+
     fn update_somite_verocities(&self, py: Python, time_delta: f64, new_forces: &Vec<Coordinate>) {
         // update somite verocities based on Velret's method
         // v_{t+1} = v_{t} +  \delta \frac{t (f_{t, x_t} + f_{t+1, x_{t+1}})}{2}
+        let conf = self.config(py);
         for (i, s) in self.somites(py).iter().enumerate() {
-            let mut new_verocity = s.get_verocity() + (s.get_force() + new_forces[i]) * 0.5 * time_delta / s.mass;
+            let old_verocity = s.get_verocity();
+            let mut new_verocity = old_verocity + (s.get_force() + new_forces[i]) * 0.5 * time_delta / s.mass;
             if s.is_gripping() {
                 new_verocity.z = 0.; // cannot move if gripping
-            } else if self.path_heights(py).is_on_ground(s) {
+            } else if conf.contact_k == 0. && self.path_heights(py).is_on_ground(s, self.simulation_time(py).get()) {
+                // no compliant contact force configured: fall back to the original
+                // hard clamp
                 new_verocity.z = new_verocity.z.max(0.);
+            } else if conf.contact_k != 0. && old_verocity.z < 0. && new_verocity.z > 0.
+                && self.path_heights(py).is_on_ground(s, self.simulation_time(py).get()) {
+                // the compliant contact force pushed the somite back out this step;
+                // scale the separation velocity by the restitution coefficient
+                // instead of keeping the full compliant-force result
+                new_verocity.z *= conf.contact_restitution;
             }
             s.set_verocity(new_verocity);
         }
@@ -605,27 +1503,45 @@ impl Caterpillar {
         }
     }
 
-    fn calculate_force_on_somites(&self, py: Python, time_delta: f64) -> Vec<Coordinate> {
-        // calculate force from friction, tension, dumping, etc.
-        // collect temporary force applied on somites and reset temp_forces instance variable
+    /// Drain `temp_forces` (the externally-applied, single-step force set via
+    /// `set_force_on_somite`) exactly once per outer step. Multi-evaluation
+    /// integrators (`step_rk4`, `step_semi_implicit_euler`) must call this once and
+    /// reuse the same snapshot across every stage/iteration's `evaluate_forces` call,
+    /// rather than re-draining (which would zero it after the first evaluation and
+    /// silently drop the external force from every later stage).
+    fn drain_temp_forces(&self, py: Python) -> Vec<Coordinate> {
+        self.temp_forces(py)
+            .iter()
+            .map(|f| f.replace(Coordinate::zero()))
+            .collect::<Vec<Coordinate>>()
+    }
 
+    /// Pure force/torque field evaluation at the somites' current position/velocity:
+    /// every internal force (gravity, springs, friction/gripping, self-collision,
+    /// ground contact) plus `external_forces`, with none of `calculate_force_on_somites`'s
+    /// once-per-step side effects (draining `temp_forces`, toggling grip state,
+    /// accumulating the grasp wrench). Safe to call more than once per step, e.g. once
+    /// per Runge-Kutta stage or fixed-point iteration, against the same
+    /// `external_forces` snapshot.
+    fn evaluate_forces(&self, py: Python, time_delta: f64, external_forces: &Vec<Coordinate>) -> (Vec<Coordinate>, Vec<Coordinate>) {
         self.profiler(py).borrow_mut().check("start calculating force");
 
-        let mut new_forces = self.temp_forces(py)
-            .iter()
-            .map(|f| f.replace(Coordinate::zero()))
-            .collect::<Vec<Coordinate>>();
+        let mut new_forces = external_forces.clone();
         self.profiler(py).borrow_mut().check("add temporary force");
 
         new_forces = self.add_gravitational_forces(py, new_forces);
         self.profiler(py).borrow_mut().check("add gravity");
 
         let conf = self.config(py);
-        new_forces = self.add_spring_forces(py, conf.sp_k, conf.sp_natural_length, new_forces);
-        self.profiler(py).borrow_mut().check("add spring force");
+        new_forces = self.add_inner_spring_forces(
+            py, conf.sp_k, conf.dp_c, conf.sp_natural_length, conf.goal_spring_k, conf.goal_spring_c, new_forces,
+        );
+        self.profiler(py).borrow_mut().check("add inner spring force");
 
-        new_forces = self.add_dumper_forces(py, conf.dp_c, new_forces);
-        self.profiler(py).borrow_mut().check("add dumper force");
+        // torque each joint's torsion springs exert on their center somite, accumulated
+        // alongside new_forces and applied (via update_somite_torques) only once the
+        // step's full resultant is known, exactly like new_forces itself
+        let mut new_torques = vec![Coordinate::zero(); self.somites(py).len()];
 
         // vertical torsion force coming from material mechanical
         let vertical_ts = torsion_spring::TorsionSpring::new(
@@ -633,7 +1549,9 @@ impl Caterpillar {
         );
         self.profiler(py).borrow_mut().check("create material torsion spring");
 
-        new_forces = self.add_material_torsion_spring_forces(py, vertical_ts, time_delta, new_forces);
+        let (new_forces_, new_torques_) = self.add_material_torsion_spring_forces(py, vertical_ts, time_delta, new_forces, new_torques);
+        new_forces = new_forces_;
+        new_torques = new_torques_;
         self.profiler(py).borrow_mut().check("add material torsion spring forces");
 
         // vertical torsion force comming from actuator
@@ -675,8 +1593,8 @@ impl Caterpillar {
         self.profiler(py).borrow_mut().check("calculate discrepancy angle");
 
         // calculate tension applied on each actuator for external reference
-        let (rtts_tensions, mut new_forces) = self.calculate_and_add_rtts_forces(
-            py, &vertical_realtime_tunable_ts, &vertical_discrepancy_angles, new_forces);
+        let (rtts_tensions, mut new_forces, new_torques) = self.calculate_and_add_rtts_forces(
+            py, &vertical_realtime_tunable_ts, &vertical_discrepancy_angles, new_forces, new_torques);
         self.profiler(py).borrow_mut().check("calculate and add rtts' forces");
 
         for (tension, tension_memo) in rtts_tensions.into_iter().zip(self.realtime_tunable_torsion_spring_tensions(py).into_iter()) {
@@ -684,73 +1602,154 @@ impl Caterpillar {
         }
         self.profiler(py).borrow_mut().check("save rtts' forces for reference");
 
-        self.update_grippers(py);
-        self.profiler(py).borrow_mut().check("update grippers");
-
-        new_forces = self.add_gripping_forces(py, new_forces);
+        let (new_forces_, new_torques) = self.add_gripping_forces(py, new_forces, new_torques, time_delta);
+        new_forces = new_forces_;
         self.profiler(py).borrow_mut().check("add gripping forces");
 
+        // push apart any somites (not directly connected) whose bodies overlap
+        new_forces = self.add_self_collision_forces(py, new_forces);
+        self.profiler(py).borrow_mut().check("add self collision forces");
+
         // if a somite is on the ground, z-axis negative force is canceled
-        self.mask_force_on_landing(py, new_forces)
+        let new_forces = self.mask_force_on_landing(py, new_forces);
+
+        (new_forces, new_torques)
+    }
+
+    /// Single-evaluation entry point for integrators (`step_verlet`, `step_xpbd`) that
+    /// only ever evaluate the force field once per step: drains `temp_forces`, toggles
+    /// grip state once against the somites' current (true, non-displaced) position,
+    /// evaluates the resulting force field, then accumulates the grasp wrench from the
+    /// gripping/ground-contact forces that evaluation just finalized. Multi-evaluation
+    /// integrators must not call this per stage/iteration — see `evaluate_forces`.
+    fn calculate_force_on_somites(&self, py: Python, time_delta: f64) -> (Vec<Coordinate>, Vec<Coordinate>) {
+        let external_forces = self.drain_temp_forces(py);
+        self.update_grippers(py);
+        self.profiler(py).borrow_mut().check("update grippers");
+        let (new_forces, new_torques) = self.evaluate_forces(py, time_delta, &external_forces);
+        self.accumulate_resultant_wrench(py);
+        (new_forces, new_torques)
+    }
+
+    fn update_somite_torques(&self, py: Python, new_torques: &Vec<Coordinate>) {
+        for (i, s) in self.somites(py).iter().enumerate() {
+            s.set_torque(new_torques[i]);
+        }
     }
 
-    /// mask negative z force if a somite is on ground
-    /// this process should be the very end of resultant force calculation
+    /// Resolve ground contact, the very end of resultant force calculation: with
+    /// `config.contact_k` set, add a compliant penalty normal force `F_n =
+    /// contact_k*d - contact_c*v_z` (the damping term only while `v_z < 0`) for every
+    /// somite penetrating the ground by depth `d`, recorded per-somite for
+    /// `contact_force_z()`; with `contact_k` unset (0, the default), fall back to the
+    /// original hard clamp of negative z force to 0.
     fn mask_force_on_landing(&self, py: Python, mut forces: Vec<Coordinate>) -> Vec<Coordinate> {
+        let conf = self.config(py);
+        let t = self.simulation_time(py).get();
         for (i, s) in self.somites(py).iter().enumerate() {
-            if self.path_heights(py).is_on_ground(s) {
-                forces[i].z = forces[i].z.max(0.)
+            if conf.contact_k == 0. {
+                if self.path_heights(py).is_on_ground(s, t) {
+                    forces[i].z = forces[i].z.max(0.);
+                }
+                continue;
             }
+
+            let depth = self.path_heights(py).get_height(s.get_position().x, t) + s.radius - s.get_position().z;
+            let normal_force = if depth > 0. {
+                let v_z = s.get_verocity().z;
+                let damping = if v_z < 0. { -conf.contact_c * v_z } else { 0. };
+                (conf.contact_k * depth + damping).max(0.)
+            } else {
+                0.
+            };
+            self.ground_contact_forces(py)[i].set(normal_force);
+            forces[i].z += normal_force;
         }
         forces
     }
 
+    /// Gravity plus ambient medium drag, decomposed per-somite into tangential and
+    /// normal components and applied through `Dynamics::calculate_environmental_force`
+    /// so callers don't re-derive `mass * gravity` by hand. Under the Step
+    /// interpolation mode this is the long-standing single global `gravity_vector`
+    /// applied uniformly; under Linear, each somite instead gets the configured
+    /// `gravity_vector` projected onto the local tangent/normal frame of the path's
+    /// slope at that somite's x position, so a curved profile produces a continuously
+    /// varying effective grade (still honouring `set_gravity_angle`/`set_gravity_vector`)
+    /// instead of one constant incline.
     fn add_gravitational_forces(&self, py: Python, mut forces: Vec<Coordinate>) -> Vec<Coordinate> {
-        let gravity_angle = self.gravity_angle(py).get();
+        let gravity_direction = self.gravity_vector(py).get();
+        let path_heights = self.path_heights(py);
+        let t = self.simulation_time(py).get();
+        let medium_friction_coeff = self.config(py).medium_friction_coeff;
+        let dynamics = self.dynamics(py);
         for (i, s) in self.somites(py).iter().enumerate() {
-            forces[i].z += -GRAVITATIONAL_ACCELERATION * s.mass * gravity_angle.cos();
-            forces[i].x += -GRAVITATIONAL_ACCELERATION * s.mass * gravity_angle.sin();
+            let direction = match path_heights.profile() {
+                path_heights::InterpolationMode::Step => gravity_direction,
+                path_heights::InterpolationMode::Linear => {
+                    let angle = path_heights.get_slope(s.get_position().x, t).atan();
+                    let (sin_a, cos_a) = (angle.sin(), angle.cos());
+                    Coordinate::new(
+                        cos_a * gravity_direction.x + sin_a * gravity_direction.z,
+                        gravity_direction.y,
+                        -sin_a * gravity_direction.x + cos_a * gravity_direction.z,
+                    )
+                },
+            };
+            let environment = Environment::new(direction * GRAVITATIONAL_ACCELERATION, medium_friction_coeff);
+            forces[i] += dynamics.calculate_environmental_force(s, &environment);
         }
         forces
     }
 
-    fn add_spring_forces(
+    /// Inner body spring-damper holding each pair of adjacent somites at `rest_length`
+    /// apart, applied equal-and-opposite so the chain neither gains nor loses momentum,
+    /// plus an independent goal spring-damper on every somite whose goal position has
+    /// been set via `set_goal_position` (driving that target over time is how
+    /// actuation such as peristalsis is expressed as a time-varying rest configuration).
+    fn add_inner_spring_forces(
         &self,
         py: Python,
-        spring_constant: f64,
-        natural_length: f64,
+        inner_spring_k: f64,
+        inner_spring_c: f64,
+        rest_length: f64,
+        goal_spring_k: f64,
+        goal_spring_c: f64,
         mut forces: Vec<Coordinate>,
     ) -> Vec<Coordinate> {
-        let sp = spring::Spring::new(spring_constant, natural_length);
-        for i in 0..(self.somites(py).len() - 1) {
-            forces[i] += sp.force(
-                self.somites(py)[i + 1].get_position(),
-                self.somites(py)[i].get_position(),
-            );
-            forces[i + 1] += sp.force(
-                self.somites(py)[i].get_position(),
-                self.somites(py)[i + 1].get_position(),
+        let dynamics = self.dynamics(py);
+        let body = BodyConfig::new(inner_spring_k, inner_spring_c, goal_spring_k, goal_spring_c);
+        let somites = self.somites(py);
+        for i in 0..(somites.len() - 1) {
+            let force = dynamics.calculate_inner_spring_force(
+                &body,
+                somites[i].get_position(),
+                somites[i + 1].get_position(),
+                somites[i].get_verocity(),
+                somites[i + 1].get_verocity(),
+                rest_length,
             );
+            forces[i] += force;
+            forces[i + 1] -= force;
+        }
+        for (i, s) in somites.iter().enumerate() {
+            if let Some(goal_position) = s.get_goal_position() {
+                forces[i] += dynamics.calculate_goal_force(&body, s.get_position(), *goal_position, s.get_verocity());
+            }
         }
         forces
     }
 
-    fn add_dumper_forces(
-        &self,
-        py: Python,
-        dumping_coeff: f64,
-        mut forces: Vec<Coordinate>,
-    ) -> Vec<Coordinate> {
-        let dp = dumper::Dumper::new(dumping_coeff);
-        for i in 0..(self.somites(py).len() - 1) {
-            forces[i] += dp.force(
-                self.somites(py)[i + 1].get_verocity(),
-                self.somites(py)[i].get_verocity(),
-            );
-            forces[i + 1] += dp.force(
-                self.somites(py)[i].get_verocity(),
-                self.somites(py)[i + 1].get_verocity(),
-            );
+    /// Resolve self-collisions by adding equal-and-opposite penalty+damping forces
+    /// for every pair of (not directly connected) somites whose bodies overlap.
+    fn add_self_collision_forces(&self, py: Python, mut forces: Vec<Coordinate>) -> Vec<Coordinate> {
+        let somites = self.somites(py);
+        let dynamics = self.dynamics(py);
+        for contact in collision::find_contacts(&somites) {
+            let relative_velocity = somites[contact.j].get_verocity() - somites[contact.i].get_verocity();
+            let force = dynamics.calculate_contact_force(contact.penetration, contact.normal, relative_velocity);
+            forces[contact.i] -= force;
+            forces[contact.j] += force;
         }
         forces
     }
@@ -761,7 +1760,8 @@ impl Caterpillar {
         t_spring: torsion_spring::TorsionSpring,
         time_delta: f64,
         mut forces: Vec<Coordinate>,
-    ) -> Vec<Coordinate> {
+        mut torques: Vec<Coordinate>,
+    ) -> (Vec<Coordinate>, Vec<Coordinate>) {
         let config = self.config(py);
         let somites = self.somites(py);
         let previous_vertical_ts_angles = self.previous_vertical_torsion_spring_angles(py);
@@ -781,13 +1781,14 @@ impl Caterpillar {
             let dumping_torque = -dumping_coeff * angular_velocity; // anti-clock-wise is positive rotation
 
             // torsion spring at i+1 th somite
-            let (force_on_t, force_on_b) = t_spring.force_to_target_angle(&pos_base, &pos_center, &pos_tip, current_angle, 0.0, dumping_torque);
+            let (force_on_t, force_on_b, torque) = t_spring.force_to_target_angle(&pos_base, &pos_center, &pos_tip, current_angle, 0.0, dumping_torque);
 
             forces[i - 1] += force_on_b;
             forces[i] -= force_on_b + force_on_t; // reaction
             forces[i + 1] += force_on_t;
+            torques[i] += torque;
         }
-        forces
+        (forces, torques)
     }
 
     fn calculate_and_add_rtts_forces(
@@ -796,7 +1797,8 @@ impl Caterpillar {
         t_spring: &torsion_spring::TorsionSpring,
         discrepancy_angles: &Vec<f64>,
         mut forces: Vec<Coordinate>,
-    ) -> (Vec<f64>, Vec<Coordinate>) {
+        mut torques: Vec<Coordinate>,
+    ) -> (Vec<f64>, Vec<Coordinate>, Vec<Coordinate>) {
         // tension i is force applied to torsion spring on i - 1 th somite
         let somites = self.somites(py);
 
@@ -808,7 +1810,7 @@ impl Caterpillar {
 
         for i in 1..(self.somites(py).len() - 1) {
             // torsion spring at i+1 th somite
-            let (force_on_t, force_on_b) = t_spring.force_on_discrepancy(
+            let (force_on_t, force_on_b, torque) = t_spring.force_on_discrepancy(
                 somites[i - 1].get_position(), somites[i].get_position(), somites[i + 1].get_position(), discrepancy_angles[i - 1]);
 
             tensions.push(discrepancy_angles[i - 1].signum() * force_on_t.norm());
@@ -816,42 +1818,91 @@ impl Caterpillar {
             forces[i - 1] += force_on_b;
             forces[i] -= force_on_b + force_on_t; // reaction
             forces[i + 1] += force_on_t;
+            torques[i] += torque;
         }
-        (tensions, forces)
+        (tensions, forces, torques)
     }
 
     fn update_grippers(&self, py: Python) {
         let somites = self.somites(py);
         let dynamics = self.dynamics(py);
         let path_heights = self.path_heights(py);
+        let t = self.simulation_time(py).get();
         for (somite_id, oscillator) in self.gripping_oscillator_ids(py).iter().zip(self.gripping_oscillators(py).iter()) {
             let mut s = &somites[*somite_id];
-            if dynamics.should_grip(s, oscillator, path_heights) { s.grip(); }
+            if dynamics.should_grip(s, oscillator, path_heights, t) { s.grip(); }
             else if dynamics.should_release(s, oscillator) { s.release(); }
         }
     }
 
     /// add shear force, i.e., force long to x axis, and force along z axis caused by gripper
-    fn add_gripping_forces(&self, py: Python, mut forces: Vec<Coordinate>) -> Vec<Coordinate> {
+    fn add_gripping_forces(&self, py: Python, mut forces: Vec<Coordinate>, mut torques: Vec<Coordinate>, time_delta: f64) -> (Vec<Coordinate>, Vec<Coordinate>) {
         let dynamics = self.dynamics(py);
         let path_heights = self.path_heights(py);
+        let t = self.simulation_time(py).get();
         for (i, (s, mut gripper)) in self.somites(py).iter().zip(self.gripping_forces(py).into_iter()).enumerate() {
-            if let Some(gp) = s.get_gripping_point() { // grip point being set means the somite has a leg
-                let gripping_force = dynamics.calculate_gripping_force(&s, &gp, &forces[i]);
+            if s.get_gripping_point().is_some() { // grip point being set means the somite has a leg
+                let (gripping_force, torque) = dynamics.calculate_gripping_force(&s, &forces[i]);
                 forces[i] += gripping_force;
+                torques[i] += torque;
                 gripper.set(gripping_force); // for external reference
-            } else if path_heights.is_on_ground(s) {
-                let friction_x = dynamics.calculate_friction(&s, &forces[i]);
-                forces[i].x += friction_x;
+            } else if path_heights.is_on_ground(s, t) {
+                let (friction, torque) = dynamics.calculate_friction(&s, &forces[i], time_delta);
+                forces[i] += friction;
+                torques[i] += torque;
                 gripper.set(Coordinate::zero()); // for external reference
             }
         }
-        forces
+        (forces, torques)
     }
 
     fn order2gripping_oscillator_id(&self, py: Python, i: usize) -> usize {
         self.gripping_oscillator_ids(py)[i]
     }
+
+    /// Reduce this step's gripping/ground-contact forces (`gripping_forces`, set by
+    /// `add_gripping_forces`, plus the compliant penalty normal force in
+    /// `ground_contact_forces`, set by `mask_force_on_landing`) into a single resultant
+    /// wrench for grasp analysis. Each interacting somite's force is turned into a
+    /// wrench `(force, r x force)` with `r` the lever arm from
+    /// `calculate_center_of_mass` to the somite's gripping point (or, for a plain
+    /// ground-contacting somite with no gripper, its own position); the per-somite wrenches are summed and
+    /// rotated into the body frame `body_orientation` reports, then stashed in
+    /// `grasp_wrench` for `resultant_wrench()`.
+    fn accumulate_resultant_wrench(&self, py: Python) {
+        let somites = self.somites(py);
+        let center_of_mass = self.calculate_center_of_mass(py);
+
+        let mut resultant_force = Coordinate::zero();
+        let mut resultant_torque = Coordinate::zero();
+        for (s, gripper) in somites.iter().zip(self.gripping_forces(py).into_iter()) {
+            let force = gripper.get();
+            let r = match s.get_gripping_point() {
+                Some(gripping_point) => *gripping_point - center_of_mass,
+                None => s.get_position() - center_of_mass,
+            };
+            resultant_force += force;
+            resultant_torque += r.cross_product(force);
+        }
+        for (i, s) in somites.iter().enumerate() {
+            let normal_force = self.ground_contact_forces(py)[i].get();
+            if normal_force == 0. {
+                continue;
+            }
+            let force = Coordinate::new(0., 0., normal_force);
+            let r = s.get_position() - center_of_mass;
+            resultant_force += force;
+            resultant_torque += r.cross_product(force);
+        }
+
+        let tail = somites.first().unwrap().get_position();
+        let head = somites.last().unwrap().get_position();
+        let orientation = Quaternion::from_basis(head - tail, Coordinate::new(0., 0., 1.));
+
+        // the attitude maps body axes to world axes; its conjugate takes world to body
+        let to_body = orientation.conjugate();
+        self.grasp_wrench(py).set((to_body.rotate(resultant_force), to_body.rotate(resultant_torque)));
+    }
 }
 
 fn phase2torsion_spring_target_angle(phase: f64, range_min: f64, range_max: f64) -> f64 {
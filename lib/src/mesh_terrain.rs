@@ -0,0 +1,177 @@
+use coordinate::Coordinate;
+
+/// A single triangle of a terrain mesh, tested against a somite's motion segment via
+/// segment-plane intersection followed by a barycentric inside-triangle check.
+#[derive(Copy, Clone, Debug)]
+pub struct Triangle {
+    pub a: Coordinate,
+    pub b: Coordinate,
+    pub c: Coordinate,
+}
+
+impl Triangle {
+    pub fn new(a: Coordinate, b: Coordinate, c: Coordinate) -> Self {
+        Triangle { a: a, b: b, c: c }
+    }
+
+    pub fn normal(&self) -> Coordinate {
+        (self.b - self.a).cross_product(self.c - self.a)
+    }
+
+    /// Intersect the motion segment `p -> q` with this triangle: solve
+    /// `u = (dot(n,a) - dot(n,p)) / dot(n, q-p)` for where the segment crosses the
+    /// triangle's plane, then confirm the hit point actually lies inside the
+    /// triangle with a barycentric test. Returns `(u, outward_normal)` on a hit with
+    /// `u` in `[0, 1]`, with the normal oriented against the segment's direction (so
+    /// callers can treat it as "the way to push back out").
+    pub fn intersect_segment(&self, p: Coordinate, q: Coordinate) -> Option<(f64, Coordinate)> {
+        let n = self.normal();
+        if n.norm() < 1.0e-12 {
+            return None; // degenerate triangle
+        }
+
+        let direction = q - p;
+        let denom = n.inner_product(direction);
+        if denom.abs() < 1.0e-12 {
+            return None; // segment parallel to the triangle's plane
+        }
+
+        let u = (n.inner_product(self.a) - n.inner_product(p)) / denom;
+        if u < 0. || u > 1. {
+            return None;
+        }
+
+        let hit = p + direction * u;
+        if !self.contains(hit) {
+            return None;
+        }
+
+        let outward = if denom > 0. { n * -1. } else { n };
+        Some((u, outward / outward.norm()))
+    }
+
+    /// Barycentric inside-triangle test for a point already known to lie on the
+    /// triangle's plane.
+    fn contains(&self, point: Coordinate) -> bool {
+        let v0 = self.c - self.a;
+        let v1 = self.b - self.a;
+        let v2 = point - self.a;
+
+        let dot00 = v0.inner_product(v0);
+        let dot01 = v0.inner_product(v1);
+        let dot02 = v0.inner_product(v2);
+        let dot11 = v1.inner_product(v1);
+        let dot12 = v1.inner_product(v2);
+
+        let denom = dot00 * dot11 - dot01 * dot01;
+        if denom.abs() < 1.0e-12 {
+            return false; // degenerate triangle
+        }
+        let u = (dot11 * dot02 - dot01 * dot12) / denom;
+        let v = (dot00 * dot12 - dot01 * dot02) / denom;
+        u >= 0. && v >= 0. && u + v <= 1.
+    }
+}
+
+/// A point of contact between a somite's motion segment and the terrain mesh: how
+/// far along the segment (`u` in `[0, 1]`) the hit occurred, and the surface's
+/// outward normal there.
+pub struct Contact {
+    pub u: f64,
+    pub normal: Coordinate,
+}
+
+/// Terrain represented as an explicit triangle mesh, resolved via segment-triangle
+/// intersection instead of `PathHeights`'s 1-D height-profile-keyed-by-x model, so
+/// slopes, overhangs and lateral walls can be represented. An empty mesh (the
+/// default) means no mesh terrain is loaded; callers should fall back to the
+/// original height-profile path in that case.
+pub struct MeshTerrain {
+    triangles: Vec<Triangle>,
+}
+
+impl MeshTerrain {
+    pub fn new() -> Self {
+        MeshTerrain { triangles: Vec::new() }
+    }
+
+    pub fn add_triangle(&mut self, a: Coordinate, b: Coordinate, c: Coordinate) {
+        self.triangles.push(Triangle::new(a, b, c));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// The nearest contact (smallest `u`) along the motion segment `p -> q` across
+    /// every triangle in the mesh, or `None` if the segment misses all of them.
+    pub fn nearest_contact(&self, p: Coordinate, q: Coordinate) -> Option<Contact> {
+        let mut nearest: Option<Contact> = None;
+        for triangle in &self.triangles {
+            if let Some((u, normal)) = triangle.intersect_segment(p, q) {
+                let is_nearer = match nearest {
+                    Some(ref contact) => u < contact.u,
+                    None => true,
+                };
+                if is_nearer {
+                    nearest = Some(Contact { u: u, normal: normal });
+                }
+            }
+        }
+        nearest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ground_triangle() -> Triangle {
+        Triangle::new(
+            Coordinate::new(-10., -10., 0.),
+            Coordinate::new(10., -10., 0.),
+            Coordinate::new(0., 10., 0.),
+        )
+    }
+
+    #[test]
+    fn test_intersect_segment_hits_flat_ground() {
+        let t = ground_triangle();
+        let hit = t.intersect_segment(Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., -1.));
+        assert!(hit.is_some());
+        let (u, normal) = hit.unwrap();
+        assert!((u - 0.5).abs() < 1.0e-10);
+        assert!((normal - Coordinate::new(0., 0., 1.)).norm() < 1.0e-10);
+    }
+
+    #[test]
+    fn test_intersect_segment_misses_outside_triangle() {
+        let t = ground_triangle();
+        let hit = t.intersect_segment(Coordinate::new(100., 100., 1.), Coordinate::new(100., 100., -1.));
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn test_intersect_segment_misses_when_not_crossing_plane() {
+        let t = ground_triangle();
+        let hit = t.intersect_segment(Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., 2.));
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn test_nearest_contact_picks_closest_triangle() {
+        let mut mesh = MeshTerrain::new();
+        mesh.add_triangle(
+            Coordinate::new(-10., -10., 0.),
+            Coordinate::new(10., -10., 0.),
+            Coordinate::new(0., 10., 0.),
+        );
+        mesh.add_triangle(
+            Coordinate::new(-10., -10., -5.),
+            Coordinate::new(10., -10., -5.),
+            Coordinate::new(0., 10., -5.),
+        );
+        let contact = mesh.nearest_contact(Coordinate::new(0., 0., 1.), Coordinate::new(0., 0., -10.)).unwrap();
+        assert!((contact.u - (1. / 11.)).abs() < 1.0e-10);
+    }
+}
@@ -0,0 +1,67 @@
+// Re-exports float primitives behind a `libm` feature so long physics runs can be
+// made bit-reproducible across platforms/toolchains, where `std`'s last-bit results
+// aren't guaranteed identical. The default build uses `std` for speed.
+
+#[cfg(not(feature = "libm"))]
+pub fn acos(x: f64) -> f64 {
+    x.acos()
+}
+
+#[cfg(feature = "libm")]
+pub fn acos(x: f64) -> f64 {
+    libm::acos(x)
+}
+
+#[cfg(not(feature = "libm"))]
+pub fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+#[cfg(feature = "libm")]
+pub fn sqrt(x: f64) -> f64 {
+    libm::sqrt(x)
+}
+
+#[cfg(not(feature = "libm"))]
+pub fn sin(x: f64) -> f64 {
+    x.sin()
+}
+
+#[cfg(feature = "libm")]
+pub fn sin(x: f64) -> f64 {
+    libm::sin(x)
+}
+
+#[cfg(not(feature = "libm"))]
+pub fn signum(x: f64) -> f64 {
+    x.signum()
+}
+
+#[cfg(feature = "libm")]
+pub fn signum(x: f64) -> f64 {
+    if x >= 0. {
+        1.
+    } else {
+        -1.
+    }
+}
+
+#[cfg(not(feature = "libm"))]
+pub fn round(x: f64) -> f64 {
+    x.round()
+}
+
+#[cfg(feature = "libm")]
+pub fn round(x: f64) -> f64 {
+    libm::round(x)
+}
+
+// `libm` has no powi analogue; this multiplies `x` by itself `n` times, which is exact
+// for small non-negative exponents and returns 1 for `n <= 0`.
+pub fn powi(x: f64, n: i32) -> f64 {
+    let mut result = 1.;
+    for _ in 0..n {
+        result *= x;
+    }
+    result
+}
@@ -1,65 +1,387 @@
+use std::f64::consts::PI;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use coordinate::Coordinate;
 use somite::Somite;
 
-const STUCK_EPSILON: f64 = 10e-3;
+/// Sinusoidal vertical motion of a section, set via `PathHeights::set_moving`: the
+/// section's height at time `t` is `base_height + amplitude * sin(2*pi*t/period +
+/// phase)`, so a static section is simply the `amplitude == 0` special case.
+#[derive(Clone, Copy)]
+struct Oscillation {
+    amplitude: f64,
+    period: f64,
+    phase: f64,
+}
+
+impl Oscillation {
+    fn offset(&self, t: f64) -> f64 {
+        self.amplitude * (2. * PI * t / self.period + self.phase).sin()
+    }
+}
+
+/// A point of contact between a somite and the Step terrain's current surface (floor
+/// or riser), returned by `PathHeights::contact`: how deep the somite has penetrated
+/// along `normal`, and the unit outward normal to push back along.
+pub struct Contact {
+    pub penetration: f64,
+    pub normal: Coordinate,
+}
+
+/// Interpolation mode applied to the stored `(start_point, height)` breakpoints.
+#[derive(Clone, Copy, PartialEq)]
+pub enum InterpolationMode {
+    /// Treat breakpoints as a step function (the long-standing behavior): height is
+    /// constant within each section, with a flat discontinuity at each start_point.
+    Step,
+    /// Linearly interpolate height (and slope) between adjacent breakpoints, giving a
+    /// continuously varying grade instead of discrete steps.
+    Linear,
+}
+
+impl InterpolationMode {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "step" => Ok(InterpolationMode::Step),
+            "linear" => Ok(InterpolationMode::Linear),
+            _ => Err(format!("unknown interpolation mode: {}", name)),
+        }
+    }
+}
+
+impl Default for InterpolationMode {
+    fn default() -> Self {
+        InterpolationMode::Step
+    }
+}
 
 /// PathHeights holds stepwise path heights.
 /// Each step (section) is represented by its start point and height.
 pub struct PathHeights {
     start_points: Vec<f64>,
     heights: Vec<f64>,
+    // per-section sinusoidal motion set via set_moving; None (the default) means the
+    // section's height is just the static `heights[i]` entry
+    oscillations: Vec<Option<Oscillation>>,
+    profile: InterpolationMode,
 }
 
 impl PathHeights {
     /// Create new path.
     /// Default to plain path, i.e., {0.: 0.}
     pub fn new() -> Self {
-        PathHeights{start_points: vec![0.], heights: vec![0.]}
+        PathHeights{start_points: vec![0.], heights: vec![0.], oscillations: vec![None], profile: InterpolationMode::default()}
+    }
+
+    /// Select step vs. linearly-interpolated ground; kept as a separate setter (rather
+    /// than a `new` argument) so every existing `PathHeights::new()` call site,
+    /// including the HDF5 and PyDict loaders, stays untouched and defaults to Step.
+    pub fn set_profile(&mut self, profile: InterpolationMode) {
+        self.profile = profile;
     }
 
-    /// set inserts new section beginning and its height
+    pub fn profile(&self) -> InterpolationMode {
+        self.profile
+    }
+
+    /// Insert a new static section at the position that keeps `start_points` sorted
+    /// ascending, which `is_on_ground`/`get_height`/`bracket` rely on to binary-search
+    /// in O(log n) instead of scanning; rejects an exact-duplicate start_point rather
+    /// than silently shadowing the existing section.
     pub fn set(&mut self, start_point: f64, height: f64) -> Result<(), String> {
+        self.insert_section(start_point, height, None)
+    }
+
+    /// Insert a section whose height oscillates sinusoidally over the simulation
+    /// clock: `base_height + amplitude * sin(2*pi*t/period + phase)`, for platforms
+    /// the caterpillar must time its gait against. Shares `set`'s sorted-insert and
+    /// duplicate-rejection behavior.
+    pub fn set_moving(&mut self, start_point: f64, base_height: f64, amplitude: f64, period: f64, phase: f64) -> Result<(), String> {
+        if period <= 0. {
+            return Err("period must be positive".to_owned());
+        }
+        self.insert_section(start_point, base_height, Some(Oscillation{amplitude, period, phase}))
+    }
+
+    fn insert_section(&mut self, start_point: f64, height: f64, oscillation: Option<Oscillation>) -> Result<(), String> {
         if start_point < 0. {
-            Err("start_point cannot be negative".to_owned())
-        } else {
-            self.start_points.push(start_point);
-            self.heights.push(height);
-            Ok(())
+            return Err("start_point cannot be negative".to_owned());
         }
+        let index = self.start_points.partition_point(|&sp| sp < start_point);
+        if index < self.start_points.len() && self.start_points[index] == start_point {
+            return Err(format!("start_point {} is already set", start_point));
+        }
+        self.start_points.insert(index, start_point);
+        self.heights.insert(index, height);
+        self.oscillations.insert(index, oscillation);
+        Ok(())
     }
 
-    /// is_on_ground returns true if a given object is on the ground, and false otherwise 
-    pub fn is_on_ground(&self, s: &Somite) -> bool {
+    /// Height of section `index` at time `t`: its base height, plus a sinusoidal
+    /// offset if `set_moving` gave it one.
+    fn effective_height(&self, index: usize, t: f64) -> f64 {
+        let base = self.heights[index];
+        match self.oscillations[index] {
+            Some(oscillation) => base + oscillation.offset(t),
+            None => base,
+        }
+    }
+
+    /// is_on_ground returns true if a given object is on the ground at time `t`, and
+    /// false otherwise
+    pub fn is_on_ground(&self, s: &Somite, t: f64) -> bool {
+        match self.profile {
+            InterpolationMode::Step => self.contact(s, t).map_or(false, |c| c.normal.z > 0.),
+            InterpolationMode::Linear => {
+                s.get_position().z <= self.get_height(s.get_position().x, t) + s.radius
+            },
+        }
+    }
+
+    /// Explicit collision query against the Step terrain at time `t`: the penetration
+    /// depth and outward unit normal of whichever face the somite overlaps, or `None`
+    /// if it's clear of the terrain. `section_index` always names the section whose
+    /// `start_point` is `<= pos.x`, so a somite within `radius` of that boundary while
+    /// still below the section's (possibly higher, for a rising step) plateau height
+    /// is touching the vertical riser it just walked into (normal `-x`, pushing it
+    /// back towards the lower section it came from); otherwise it's tested against
+    /// the flat floor of its own section (normal `+z`). Gives callers enough to apply
+    /// a proper reaction force/impulse along the normal, replacing the old
+    /// STUCK_EPSILON heuristic that only ever nudged `is_on_ground` towards the lower
+    /// section and left wall pushback as a blunt "cancel all +x motion" with no
+    /// regard for how far past the boundary the somite actually was.
+    pub fn contact(&self, s: &Somite, t: f64) -> Option<Contact> {
         let pos = s.get_position();
+        let i = self.section_index(pos.x);
 
-        if pos.x < self.start_points[0] {
-            return s.is_on_ground(self.heights[0]);
+        if i > 0 {
+            let start = self.start_points[i];
+            let upper = self.effective_height(i, t);
+            let lower = self.effective_height(i - 1, t);
+            if upper > lower && pos.x - start < s.radius && pos.z < upper + s.radius {
+                return Some(Contact {
+                    penetration: s.radius - (pos.x - start),
+                    normal: Coordinate::new(-1., 0., 0.),
+                });
+            }
         }
 
-        for (i, start_point) in self.start_points.iter().enumerate() {
-            if *start_point > pos.x  {
-                if pos.z < s.radius + self.heights[i-1] - STUCK_EPSILON {
-                    return s.is_on_ground(self.heights[i-2]); // use the lower ground while being blocked
+        let penetration = self.effective_height(i, t) + s.radius - pos.z;
+        if penetration >= 0. {
+            Some(Contact { penetration, normal: Coordinate::new(0., 0., 1.) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_height(&self, x: f64, t: f64) -> f64 {
+        match self.profile {
+            InterpolationMode::Step => self.effective_height(self.section_index(x), t),
+            InterpolationMode::Linear => {
+                let (i, j) = self.bracket(x);
+                let (x0, x1) = (self.start_points[i], self.start_points[j]);
+                let (h0, h1) = (self.effective_height(i, t), self.effective_height(j, t));
+                if x0 == x1 {
+                    h0
                 } else {
-                    return s.is_on_ground(self.heights[i-1]); // the first start_point is 0, thus i>1
+                    h0 + (h1 - h0) * (x - x0) / (x1 - x0)
                 }
-            }
+            },
+        }
+    }
+
+    /// Local slope `dz/dx` of the path at `x` and time `t`; always 0 under the Step
+    /// profile, since a step function's flat sections have no well-defined grade.
+    pub fn get_slope(&self, x: f64, t: f64) -> f64 {
+        match self.profile {
+            InterpolationMode::Step => 0.,
+            InterpolationMode::Linear => {
+                let (i, j) = self.bracket(x);
+                let (x0, x1) = (self.start_points[i], self.start_points[j]);
+                if x0 == x1 {
+                    0.
+                } else {
+                    (self.effective_height(j, t) - self.effective_height(i, t)) / (x1 - x0)
+                }
+            },
         }
-        if pos.z < s.radius + self.heights[self.heights.len()-1] - STUCK_EPSILON {
-            s.is_on_ground(self.heights[self.heights.len()-2]) // at least, 0 is set
+    }
+
+    /// Index of the section containing `x` under the Step profile: the last
+    /// start_point `<= x` (0 if `x` is before every breakpoint), found via
+    /// `partition_point` in O(log n) on the sorted vec `set` maintains.
+    fn section_index(&self, x: f64) -> usize {
+        self.start_points.partition_point(|&sp| sp <= x).saturating_sub(1)
+    }
+
+    /// Indices of the breakpoints bracketing `x`, via `partition_point` on the sorted
+    /// vec `set` maintains: `(i, i + 1)` straddling `x`, or `(i, i)` when `x` is at or
+    /// beyond an end, so interpolation degenerates to a flat extrapolation of the
+    /// nearest height.
+    fn bracket(&self, x: f64) -> (usize, usize) {
+        if x <= self.start_points[0] {
+            return (0, 0);
+        }
+        let index = self.start_points.partition_point(|&sp| sp <= x);
+        if index == self.start_points.len() {
+            let last = index - 1;
+            (last, last)
         } else {
-            s.is_on_ground(self.heights[self.heights.len()-1]) // at least, 0 is set
+            (index - 1, index)
         }
     }
 
-    pub fn get_height(&self, x: f64) -> f64 {
-        if x < *self.start_points.first().unwrap() {
-            return *self.heights.first().unwrap();
+    /// Build a stepwise terrain of `length` sections via a momentum-biased random walk:
+    /// each new section is spaced `(config.min_spacing, config.max_spacing)` past the
+    /// last, and its height delta is drawn from `config.step_weights` unless, with
+    /// probability `config.momentum_prob`, the previous section's delta is reused
+    /// instead — producing longer coherent slopes/stairs rather than noisy jitter. The
+    /// running height is clamped to `[config.min_height, config.max_height]`. `rng` is
+    /// called for every random draw and must return a value uniform in `[0, 1)`, so
+    /// callers control reproducibility through their own seeded generator rather than
+    /// this crate depending on one.
+    pub fn generate_random(
+        length: usize,
+        config: &GenerationConfig,
+        mut rng: impl FnMut() -> f64,
+    ) -> Result<Self, String> {
+        if length == 0 {
+            return Err("length must be positive".to_owned());
+        }
+        if config.min_spacing <= 0. || config.max_spacing <= 0. {
+            return Err("spacing bounds must be positive".to_owned());
+        }
+        if config.max_spacing < config.min_spacing {
+            return Err("max_spacing must be at least min_spacing".to_owned());
+        }
+        if config.step_weights.is_empty() {
+            return Err("step_weights must not be empty".to_owned());
+        }
+
+        let mut path_heights = PathHeights::new();
+        let mut x = 0.;
+        let mut height = 0.;
+        let mut previous_delta = 0.;
+
+        for i in 0..length {
+            if i > 0 {
+                x += config.min_spacing + rng() * (config.max_spacing - config.min_spacing);
+            }
+
+            let delta = if i > 0 && rng() < config.momentum_prob {
+                previous_delta
+            } else {
+                Self::sample_step_weights(&config.step_weights, &mut rng)
+            };
+            previous_delta = delta;
+            height = (height + delta).max(config.min_height).min(config.max_height);
+
+            if i == 0 {
+                path_heights.heights[0] = height; // replace the default flat origin in place
+            } else {
+                path_heights.set(x, height)?;
+            }
         }
-        for (i, start_point) in self.start_points.iter().enumerate() {
-            if *start_point > x {
-                return self.heights[i-1];
+
+        Ok(path_heights)
+    }
+
+    /// Draw one `(height delta, weight)` candidate from `step_weights`, weighted by
+    /// `weight`, via a single roll scaled by the total weight.
+    fn sample_step_weights(step_weights: &[(f64, f64)], rng: &mut impl FnMut() -> f64) -> f64 {
+        let total_weight: f64 = step_weights.iter().map(|&(_, weight)| weight).sum();
+        let mut roll = rng() * total_weight;
+        for &(delta, weight) in step_weights {
+            if roll < weight {
+                return delta;
             }
+            roll -= weight;
         }
-        *self.heights.last().unwrap()
+        step_weights.last().map(|&(delta, _)| delta).unwrap_or(0.)
+    }
+
+    /// Load a terrain fixture written in the `to_string`/`to_file` segment format.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+        contents.parse()
+    }
+
+    /// Write this terrain out in the `0,0.0 -> 1.5,0.2 -> 3.0,-0.1` segment format, so
+    /// it can be reloaded later via `from_file`/`from_str`.
+    pub fn to_file(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("failed to write {}: {}", path, e))
     }
-}   
\ No newline at end of file
+}
+
+/// Tunable knobs for `PathHeights::generate_random`'s biased random walk.
+#[derive(Clone)]
+pub struct GenerationConfig {
+    pub min_spacing: f64,
+    pub max_spacing: f64,
+    /// Candidate height deltas and their relative weights, e.g. `[(0.1, 1.), (-0.1,
+    /// 1.), (0., 2.)]` to favor staying level over climbing or descending.
+    pub step_weights: Vec<(f64, f64)>,
+    /// Probability in `[0, 1]` of reusing the previous section's delta instead of
+    /// resampling `step_weights`, biasing the walk toward coherent runs of slope.
+    pub momentum_prob: f64,
+    pub min_height: f64,
+    pub max_height: f64,
+}
+
+/// Serializes as the compact segment format parsed by `FromStr`: each section is
+/// `start_point,height`, chained left to right with ` -> `.
+impl fmt::Display for PathHeights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let segments = self.start_points.iter().zip(self.heights.iter())
+            .map(|(start_point, height)| format!("{},{}", start_point, height))
+            .collect::<Vec<String>>();
+        write!(f, "{}", segments.join(" -> "))
+    }
+}
+
+/// Parses the compact segment format `0,0.0 -> 1.5,0.2 -> 3.0,-0.1`: each token is
+/// `start_point,height`, and `->` chains sections left to right. Validates that
+/// start points are non-negative and strictly increasing, reporting the 1-indexed
+/// segment ("column") a malformed token was found in.
+impl FromStr for PathHeights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut path_heights = PathHeights::new();
+        let mut previous_start: Option<f64> = None;
+
+        for (column, segment) in s.split("->").enumerate() {
+            let column = column + 1;
+            let trimmed = segment.trim();
+            let (start_str, height_str) = trimmed.split_once(',')
+                .ok_or_else(|| format!("line 1, column {}: segment {:?} is missing a ','", column, trimmed))?;
+
+            let start_point = start_str.trim().parse::<f64>()
+                .map_err(|e| format!("line 1, column {}: invalid start_point {:?}: {}", column, start_str.trim(), e))?;
+            let height = height_str.trim().parse::<f64>()
+                .map_err(|e| format!("line 1, column {}: invalid height {:?}: {}", column, height_str.trim(), e))?;
+
+            if start_point < 0. {
+                return Err(format!("line 1, column {}: start_point cannot be negative", column));
+            }
+            if let Some(previous_start) = previous_start {
+                if start_point <= previous_start {
+                    return Err(format!("line 1, column {}: start points must be strictly increasing", column));
+                }
+            }
+            previous_start = Some(start_point);
+
+            if column == 1 {
+                if start_point != 0. {
+                    return Err(format!("line 1, column {}: first start_point must be 0", column));
+                }
+                path_heights.heights[0] = height;
+            } else {
+                path_heights.set(start_point, height)?;
+            }
+        }
+
+        Ok(path_heights)
+    }
+}
@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+
+/// Kuramoto-style coupling network for a fixed-size group of `PhaseOscillator`s
+/// (e.g. the body actuators or the grippers), addressed by oscillator index within
+/// that group rather than somite id. Holds a coupling-weight matrix `w[i][j]` and a
+/// desired phase-bias matrix `psi[i][j]`; both default to zero so a network no one
+/// has configured reduces to the previous uncoupled behavior.
+pub struct PhaseCoupling {
+    weights: RefCell<Vec<Vec<f64>>>,
+    phase_biases: RefCell<Vec<Vec<f64>>>,
+}
+
+impl PhaseCoupling {
+    pub fn new(size: usize) -> Self {
+        PhaseCoupling {
+            weights: RefCell::new(vec![vec![0.; size]; size]),
+            phase_biases: RefCell::new(vec![vec![0.; size]; size]),
+        }
+    }
+
+    pub fn set_weights(&self, weights: Vec<Vec<f64>>) {
+        *self.weights.borrow_mut() = weights;
+    }
+
+    pub fn set_phase_biases(&self, phase_biases: Vec<Vec<f64>>) {
+        *self.phase_biases.borrow_mut() = phase_biases;
+    }
+
+    /// `sum_j w[i][j] * sin(phases[j] - phases[i] - psi[i][j])`, given a snapshot of
+    /// every oscillator's current phase so the whole network updates simultaneously
+    /// instead of mixing pre- and post-step phases.
+    pub fn coupling_term(&self, i: usize, phases: &[f64]) -> f64 {
+        let weights = self.weights.borrow();
+        let phase_biases = self.phase_biases.borrow();
+        (0..phases.len())
+            .map(|j| weights[i][j] * (phases[j] - phases[i] - phase_biases[i][j]).sin())
+            .sum()
+    }
+}
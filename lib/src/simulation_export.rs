@@ -3,6 +3,7 @@ extern crate serde_json;
 
 use std::fs;
 use std::io;
+use std::io::prelude::*;
 use std::collections;
 use std::cell;
 
@@ -17,6 +18,34 @@ pub struct Object {
 pub struct ObjectPosition {
     pub id: String,
     pub pos: (f64, f64, f64),
+    // quaternion (w, x, y, z) attitude, now that somites carry a rigid-body orientation
+    // rather than just a translating center
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<(f64, f64, f64, f64)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verocity: Option<(f64, f64, f64)>,
+    pub gripping: bool,
+    // phase of the body actuator oscillator on this somite (None if it has none) and
+    // tension of the realtime-tunable torsion spring ending at it (None for the first
+    // and last somite)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tension: Option<f64>,
+}
+
+impl ObjectPosition {
+    pub fn new(id: String, pos: (f64, f64, f64)) -> Self {
+        ObjectPosition {
+            id: id,
+            pos: pos,
+            orientation: None,
+            verocity: None,
+            gripping: false,
+            phase: None,
+            tension: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -50,12 +79,112 @@ impl SimulationProc {
         let buf_writer = io::BufWriter::new(f);
         serde_json::to_writer(buf_writer, self).unwrap();
     }
+
+    /// Drop all recorded frames so a reset episode starts a fresh recording, without
+    /// discarding and re-registering the (unchanged) object roster.
+    pub fn clear(&self) {
+        self.frames.borrow_mut().clear();
+    }
+
+    /// The static object roster, for exporters (e.g. hdf5_export) that need it
+    /// alongside the recorded frames but outside this module.
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    /// The recorded frames, keyed by frame order, for exporters that need to walk
+    /// them without going through `save`'s JSON format.
+    pub fn frames(&self) -> cell::Ref<collections::HashMap<usize, Vec<ObjectPosition>>> {
+        self.frames.borrow()
+    }
+}
+
+/// Header written once at the top of a frame stream: the static object roster, so a
+/// reader can map later per-frame `ObjectPosition`s back to radii without re-reading
+/// them from each frame.
+#[derive(Serialize, Deserialize)]
+struct StreamHeader {
+    objects: Vec<Object>,
+}
+
+/// A single recorded frame in a stream file: its `frame_order` plus the positions
+/// (and optional orientation/velocity/gripping state) of every object that moved.
+#[derive(Serialize, Deserialize)]
+struct StreamFrame {
+    frame_order: usize,
+    positions: Vec<ObjectPosition>,
+}
+
+/// Streaming counterpart to `SimulationProc`: instead of buffering every frame in
+/// memory until a single `save()`, `add_frame` appends one newline-delimited JSON
+/// record per call and flushes immediately, so long runs don't grow memory without
+/// bound. The header (object roster) is written once by `open_stream`.
+pub struct SimulationStream {
+    writer: cell::RefCell<io::BufWriter<fs::File>>,
+    last_frame_order: cell::Cell<Option<usize>>,
+}
+
+impl SimulationStream {
+    pub fn open_stream(file_path: &str, objects: Vec<Object>) -> io::Result<Self> {
+        let f = fs::File::create(file_path)?;
+        let mut writer = io::BufWriter::new(f);
+        serde_json::to_writer(&mut writer, &StreamHeader { objects: objects }).unwrap();
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(SimulationStream {
+            writer: cell::RefCell::new(writer),
+            last_frame_order: cell::Cell::new(None),
+        })
+    }
+
+    pub fn add_frame(&self, frame_order: usize, frame: Vec<ObjectPosition>) {
+        if let Some(last) = self.last_frame_order.get() {
+            if frame_order <= last {
+                panic!("frame order incompetible");
+            }
+        }
+        self.last_frame_order.set(Some(frame_order));
+
+        let mut writer = self.writer.borrow_mut();
+        serde_json::to_writer(
+            &mut *writer,
+            &StreamFrame {
+                frame_order: frame_order,
+                positions: frame,
+            },
+        ).unwrap();
+        writer.write_all(b"\n").unwrap();
+        writer.flush().unwrap();
+    }
+}
+
+/// Read back a file written by `SimulationStream`, reconstructing the same
+/// `(objects, frame_order -> positions)` shape `SimulationProc::save` would have
+/// produced, so downstream visualization tools can replay either format alike.
+pub fn read_stream(
+    file_path: &str,
+) -> io::Result<(Vec<Object>, collections::HashMap<usize, Vec<ObjectPosition>>)> {
+    let f = fs::File::open(file_path)?;
+    let mut lines = io::BufReader::new(f).lines();
+
+    let header_line = lines.next().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::UnexpectedEof, "missing stream header")
+    })??;
+    let header: StreamHeader = serde_json::from_str(&header_line).unwrap();
+
+    let mut frames = collections::HashMap::new();
+    for line in lines {
+        let line = line?;
+        let frame: StreamFrame = serde_json::from_str(&line).unwrap();
+        frames.insert(frame.frame_order, frame.positions);
+    }
+
+    Ok((header.objects, frames))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::prelude::*;
 
     #[test]
     fn test_add_frame() {
@@ -66,31 +195,16 @@ mod tests {
         sim_proc.add_frame(
             first,
             vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (0., 0., 0.),
-                },
-                ObjectPosition {
-                    id: "s1".to_string(),
-                    pos: (1., 0., 0.),
-                },
-                ObjectPosition {
-                    id: "s2".to_string(),
-                    pos: (2., 0., 0.),
-                },
+                ObjectPosition::new("s0".to_string(), (0., 0., 0.)),
+                ObjectPosition::new("s1".to_string(), (1., 0., 0.)),
+                ObjectPosition::new("s2".to_string(), (2., 0., 0.)),
             ],
         );
         sim_proc.add_frame(
             second,
             vec![
-                ObjectPosition {
-                    id: "s1".to_string(),
-                    pos: (0., 3., 0.),
-                },
-                ObjectPosition {
-                    id: "s2".to_string(),
-                    pos: (0., 4., 0.),
-                },
+                ObjectPosition::new("s1".to_string(), (0., 3., 0.)),
+                ObjectPosition::new("s2".to_string(), (0., 4., 0.)),
             ],
         );
 
@@ -115,21 +229,11 @@ mod tests {
 
         sim_proc.add_frame(
             first,
-            vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (0., 0., 0.),
-                },
-            ],
+            vec![ObjectPosition::new("s0".to_string(), (0., 0., 0.))],
         );
         sim_proc.add_frame(
             first,
-            vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (0., 3., 0.),
-                },
-            ],
+            vec![ObjectPosition::new("s0".to_string(), (0., 3., 0.))],
         );
     }
 
@@ -142,21 +246,11 @@ mod tests {
 
         sim_proc.add_frame(
             second,
-            vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (0., 0., 0.),
-                },
-            ],
+            vec![ObjectPosition::new("s0".to_string(), (0., 0., 0.))],
         );
         sim_proc.add_frame(
             first,
-            vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (0., 3., 0.),
-                },
-            ],
+            vec![ObjectPosition::new("s0".to_string(), (0., 3., 0.))],
         );
     }
 
@@ -180,34 +274,22 @@ mod tests {
         sim_proc.add_frame(
             first,
             vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (1., 0., 0.),
-                },
-                ObjectPosition {
-                    id: "s1".to_string(),
-                    pos: (2., 0., 0.),
-                },
+                ObjectPosition::new("s0".to_string(), (1., 0., 0.)),
+                ObjectPosition::new("s1".to_string(), (2., 0., 0.)),
             ],
         );
         sim_proc.add_frame(
             second,
             vec![
-                ObjectPosition {
-                    id: "s0".to_string(),
-                    pos: (2., 1., 0.),
-                },
-                ObjectPosition {
-                    id: "s1".to_string(),
-                    pos: (3., 2., 0.),
-                },
+                ObjectPosition::new("s0".to_string(), (2., 1., 0.)),
+                ObjectPosition::new("s1".to_string(), (3., 2., 0.)),
             ],
         );
 
-        clean_file(|x| {
-            sim_proc.save(&x.file_path);
+        clean_file("test.json", |file_path| {
+            sim_proc.save(file_path);
 
-            let file = fs::File::open(&x.file_path).unwrap();
+            let file = fs::File::open(file_path).unwrap();
             let mut buf_reader = io::BufReader::new(file);
             let mut buf = String::new();
             buf_reader.read_to_string(&mut buf).unwrap();
@@ -217,15 +299,15 @@ mod tests {
             "{\
                 \"objects\":[{\"id\":\"s0\",\"rad\":2.0,\"pos\":[0.0,0.0,0.0]},{\"id\":\"s1\",\"rad\":3.0,\"pos\":[1.0,0.0,0.0]}],\
                 \"frames\":{\
-                    \"0\":[{\"id\":\"s0\",\"pos\":[1.0,0.0,0.0]},{\"id\":\"s1\",\"pos\":[2.0,0.0,0.0]}],\
-                    \"1\":[{\"id\":\"s0\",\"pos\":[2.0,1.0,0.0]},{\"id\":\"s1\",\"pos\":[3.0,2.0,0.0]}]\
+                    \"0\":[{\"id\":\"s0\",\"pos\":[1.0,0.0,0.0],\"gripping\":false},{\"id\":\"s1\",\"pos\":[2.0,0.0,0.0],\"gripping\":false}],\
+                    \"1\":[{\"id\":\"s0\",\"pos\":[2.0,1.0,0.0],\"gripping\":false},{\"id\":\"s1\",\"pos\":[3.0,2.0,0.0],\"gripping\":false}]\
                 }\
             }",
             "{\
                 \"objects\":[{\"id\":\"s0\",\"rad\":2.0,\"pos\":[0.0,0.0,0.0]},{\"id\":\"s1\",\"rad\":3.0,\"pos\":[1.0,0.0,0.0]}],\
                 \"frames\":{\
-                    \"1\":[{\"id\":\"s0\",\"pos\":[2.0,1.0,0.0]},{\"id\":\"s1\",\"pos\":[3.0,2.0,0.0]}],\
-                    \"0\":[{\"id\":\"s0\",\"pos\":[1.0,0.0,0.0]},{\"id\":\"s1\",\"pos\":[2.0,0.0,0.0]}]\
+                    \"1\":[{\"id\":\"s0\",\"pos\":[2.0,1.0,0.0],\"gripping\":false},{\"id\":\"s1\",\"pos\":[3.0,2.0,0.0],\"gripping\":false}],\
+                    \"0\":[{\"id\":\"s0\",\"pos\":[1.0,0.0,0.0],\"gripping\":false},{\"id\":\"s1\",\"pos\":[2.0,0.0,0.0],\"gripping\":false}]\
                 }\
             }",
             ];
@@ -233,15 +315,45 @@ mod tests {
         });
     }
 
-    struct TestFixture {
-        file_path: String,
+    #[test]
+    fn test_stream_round_trip() {
+        let objects = vec![
+            Object { id: "s0".to_string(), rad: 1., pos: (0., 0., 0.) },
+            Object { id: "s1".to_string(), rad: 1., pos: (1., 0., 0.) },
+        ];
+
+        clean_file("test_stream.jsonl", |file_path| {
+            let stream = SimulationStream::open_stream(file_path, objects).unwrap();
+
+            let mut first_frame = ObjectPosition::new("s0".to_string(), (0., 0., 0.));
+            first_frame.orientation = Some((1., 0., 0., 0.));
+            first_frame.verocity = Some((1., 0., 0.));
+            stream.add_frame(0, vec![first_frame]);
+
+            let mut second_frame = ObjectPosition::new("s0".to_string(), (1., 0., 0.));
+            second_frame.gripping = true;
+            stream.add_frame(1, vec![second_frame]);
+
+            let (read_objects, frames) = read_stream(file_path).unwrap();
+            assert_eq!(read_objects.len(), 2);
+            assert_eq!(frames.len(), 2);
+            assert_eq!(frames[&0][0].verocity, Some((1., 0., 0.)));
+            assert_eq!(frames[&1][0].gripping, true);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn panic_on_stream_frame_order_disturbed() {
+        clean_file("test_stream_disturbed.jsonl", |file_path| {
+            let stream = SimulationStream::open_stream(file_path, Vec::<Object>::new()).unwrap();
+            stream.add_frame(1, vec![ObjectPosition::new("s0".to_string(), (0., 0., 0.))]);
+            stream.add_frame(0, vec![ObjectPosition::new("s0".to_string(), (0., 3., 0.))]);
+        });
     }
 
-    fn clean_file<F: Fn(&TestFixture)>(f: F) {
-        let tf = TestFixture {
-            file_path: "test.json".to_string(),
-        };
-        f(&tf);
-        fs::remove_file(&tf.file_path).unwrap();
+    fn clean_file<F: Fn(&str)>(file_path: &str, f: F) {
+        f(file_path);
+        fs::remove_file(file_path).unwrap();
     }
 }
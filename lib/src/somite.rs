@@ -10,8 +10,24 @@ pub struct Somite {
     pub force: cell::Cell<coordinate::Coordinate>,
     pub radius: f64,
     pub mass: f64,
+    // rigid-body rotational state: a unit quaternion attitude driven by an angular
+    // Verlet update (see Caterpillar::update_somite_orientations), rather than forces
+    // only ever moving neighboring centers and rotation being discarded
+    pub orientation: cell::Cell<coordinate::Quaternion>,
+    pub angular_velocity: cell::Cell<coordinate::Coordinate>,
+    pub torque: cell::Cell<coordinate::Coordinate>,
+    pub moment_of_inertia: f64,
     gripping_flag: cell::Cell<bool>,
     gripping_point: cell::RefCell<coordinate::Coordinate>,
+    // set when static friction has pinned the somite in place; `stuck_point` is the
+    // position the restoring spring in `Dynamics::calculate_friction` pulls back towards
+    stuck_flag: cell::Cell<bool>,
+    stuck_point: cell::RefCell<coordinate::Coordinate>,
+    // set when a goal spring (Dynamics::calculate_goal_force) is actuating this
+    // somite; `goal_position` is the target it is pulled towards, driven over time
+    // by the caller (e.g. for peristalsis) rather than applying force directly
+    goal_flag: cell::Cell<bool>,
+    goal_position: cell::RefCell<coordinate::Coordinate>,
 }
 
 impl fmt::Display for Somite {
@@ -43,10 +59,23 @@ impl Somite {
             }),
             radius: radius,
             mass: mass,
+            orientation: cell::Cell::new(coordinate::Quaternion::identity()),
+            angular_velocity: cell::Cell::new(coordinate::Coordinate::zero()),
+            torque: cell::Cell::new(coordinate::Coordinate::zero()),
+            // sphere moment of inertia I = 0.4 * m * r^2
+            moment_of_inertia: 0.4 * mass * radius.powi(2),
             gripping_flag: cell::Cell::new(false),
             gripping_point: cell::RefCell::<coordinate::Coordinate>::new(
                 coordinate::Coordinate::zero(),
             ),
+            stuck_flag: cell::Cell::new(false),
+            stuck_point: cell::RefCell::<coordinate::Coordinate>::new(
+                coordinate::Coordinate::zero(),
+            ),
+            goal_flag: cell::Cell::new(false),
+            goal_position: cell::RefCell::<coordinate::Coordinate>::new(
+                coordinate::Coordinate::zero(),
+            ),
         }
     }
 
@@ -87,6 +116,30 @@ impl Somite {
         self.force.get()
     }
 
+    pub fn set_torque(&self, torque: coordinate::Coordinate) {
+        self.torque.set(torque);
+    }
+
+    pub fn get_torque(&self) -> coordinate::Coordinate {
+        self.torque.get()
+    }
+
+    pub fn get_orientation(&self) -> coordinate::Quaternion {
+        self.orientation.get()
+    }
+
+    pub fn set_orientation(&self, orientation: coordinate::Quaternion) {
+        self.orientation.set(orientation);
+    }
+
+    pub fn get_angular_velocity(&self) -> coordinate::Coordinate {
+        self.angular_velocity.get()
+    }
+
+    pub fn set_angular_velocity(&self, angular_velocity: coordinate::Coordinate) {
+        self.angular_velocity.set(angular_velocity);
+    }
+
     pub fn get_verocity_direction_x(&self) -> f64 {
         // if v_x > 0, return 1.0
         // if v_x < 0, return -1.0
@@ -130,6 +183,33 @@ impl Somite {
         self.gripping_flag.set(false);
     }
 
+    pub fn is_stuck(&self) -> bool {
+        self.stuck_flag.get()
+    }
+
+    pub fn stick(&self) {
+        // update stuck point only if stuck flag is false
+        if !self.stuck_flag.get() {
+            self.stuck_flag.set(true);
+            let p = self.position.get();
+            self.stuck_point.borrow_mut().x = p.x;
+            self.stuck_point.borrow_mut().y = p.y;
+            self.stuck_point.borrow_mut().z = p.z;
+        }
+    }
+
+    pub fn unstick(&self) {
+        self.stuck_flag.set(false);
+    }
+
+    pub fn get_stuck_point(&self) -> Option<cell::Ref<coordinate::Coordinate>> {
+        if self.stuck_flag.get() {
+            Some(self.stuck_point.borrow())
+        } else {
+            None
+        }
+    }
+
     pub fn get_gripping_point(&self) -> Option<cell::Ref<coordinate::Coordinate>> {
         if self.gripping_flag.get() {
             Some(self.gripping_point.borrow())
@@ -137,6 +217,29 @@ impl Somite {
             None
         }
     }
+
+    pub fn has_goal(&self) -> bool {
+        self.goal_flag.get()
+    }
+
+    pub fn set_goal_position(&self, goal_position: coordinate::Coordinate) {
+        self.goal_flag.set(true);
+        self.goal_position.borrow_mut().x = goal_position.x;
+        self.goal_position.borrow_mut().y = goal_position.y;
+        self.goal_position.borrow_mut().z = goal_position.z;
+    }
+
+    pub fn clear_goal(&self) {
+        self.goal_flag.set(false);
+    }
+
+    pub fn get_goal_position(&self) -> Option<cell::Ref<coordinate::Coordinate>> {
+        if self.goal_flag.get() {
+            Some(self.goal_position.borrow())
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -161,4 +264,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_goal_position() {
+        let s = Somite::new_still_somite(1., 2., coordinate::Coordinate::new(0., 0., 4.));
+        assert!(!s.has_goal());
+        assert!(s.get_goal_position().is_none());
+
+        s.set_goal_position(coordinate::Coordinate::new(1., 2., 3.));
+        assert!(s.has_goal());
+        assert_eq!(
+            *s.get_goal_position().unwrap(),
+            coordinate::Coordinate::new(1., 2., 3.)
+        );
+
+        s.clear_goal();
+        assert!(!s.has_goal());
+        assert!(s.get_goal_position().is_none());
+    }
+
 }
@@ -0,0 +1,107 @@
+use std::fs::File;
+use std::io;
+use std::io::Write;
+
+/// Quantities `enable_logging` can record, matching the per-step observation values
+/// already exposed to Python (get_somite_distances, get_somite_angles, somite_phases,
+/// gripper_phases, gripping_force_x, tensions).
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogColumn {
+    SomiteDistances,
+    SomiteAngles,
+    SomitePhases,
+    GripperPhases,
+    GrippingForceX,
+    Tensions,
+}
+
+impl LogColumn {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "somite_distances" => Ok(LogColumn::SomiteDistances),
+            "somite_angles" => Ok(LogColumn::SomiteAngles),
+            "somite_phases" => Ok(LogColumn::SomitePhases),
+            "gripper_phases" => Ok(LogColumn::GripperPhases),
+            "gripping_force_x" => Ok(LogColumn::GrippingForceX),
+            "tensions" => Ok(LogColumn::Tensions),
+            _ => Err(format!("unknown log column: {}", name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            LogColumn::SomiteDistances => "somite_distances",
+            LogColumn::SomiteAngles => "somite_angles",
+            LogColumn::SomitePhases => "somite_phases",
+            LogColumn::GripperPhases => "gripper_phases",
+            LogColumn::GrippingForceX => "gripping_force_x",
+            LogColumn::Tensions => "tensions",
+        }
+    }
+}
+
+/// Per-step CSV logging, enabled via `Caterpillar::enable_logging` and sampled every
+/// `every_n` steps from inside the Rust-side `steps_with_feedbacks` loop instead of a
+/// Python round-trip each iteration. Each registered column contributes a fixed
+/// number of fields to every recorded row (its length when logging was enabled);
+/// `flush` writes every recorded row out as CSV with a header naming each field.
+pub struct StepLogger {
+    columns: Vec<(LogColumn, usize)>,
+    header: Vec<String>,
+    every_n: usize,
+    rows: Vec<Vec<f64>>,
+}
+
+impl StepLogger {
+    pub fn new() -> Self {
+        StepLogger {
+            columns: Vec::new(),
+            header: Vec::new(),
+            every_n: 1,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn enable(&mut self, columns: Vec<LogColumn>, widths: Vec<usize>) {
+        self.header = columns
+            .iter()
+            .zip(widths.iter())
+            .flat_map(|(c, &w)| (0..w).map(move |i| format!("{}_{}", c.name(), i)))
+            .collect();
+        self.columns = columns.into_iter().zip(widths.into_iter()).collect();
+        self.rows.clear();
+    }
+
+    pub fn set_sample_interval(&mut self, every_n: usize) {
+        self.every_n = every_n.max(1);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.columns.is_empty()
+    }
+
+    pub fn columns(&self) -> &[(LogColumn, usize)] {
+        &self.columns
+    }
+
+    pub fn should_sample(&self, step: usize) -> bool {
+        self.is_enabled() && step % self.every_n == 0
+    }
+
+    pub fn record(&mut self, row: Vec<f64>) {
+        self.rows.push(row);
+    }
+
+    pub fn flush(&self, file_path: &str) -> io::Result<()> {
+        let mut file = File::create(file_path)?;
+        writeln!(file, "{}", self.header.join(","))?;
+        for row in &self.rows {
+            writeln!(
+                file,
+                "{}",
+                row.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,25 +1,26 @@
 use std::fmt;
 use std::f64;
+use std::cell;
 use coordinate;
+use coordinate::Scalar;
+use calculations;
+use angle::Rad;
 
 const EPSILON: f64 = 1.0e-5;
 
 #[derive(Copy, Clone)]
-pub struct TorsionSpring {
-    spring_constant_k0: f64,
-    spring_constant_k1: f64,
-    standard_vector: coordinate::Coordinate, // torsion is calculated within an orthogonal plane to the standard_vector
+pub struct TorsionSpring<T: Scalar = f64> {
+    spring_constant_k0: T,
+    spring_constant_k1: T,
+    standard_vector: coordinate::Coordinate<T>, // torsion is calculated within an orthogonal plane to the standard_vector
 }
 
-impl TorsionSpring {
-    pub fn new(
-        spring_constant_k0: f64,
-        spring_constant_k1: f64,
-        standard: coordinate::Coordinate,
-    ) -> Self {
-        let epsilon = 1.0e-10;
-        if standard.norm() <= 1. - epsilon || standard.norm() >= 1. + epsilon {
-            panic!("norm of standard_vector should be 1.0, {}", standard)
+impl<T: Scalar> TorsionSpring<T> {
+    pub fn new(spring_constant_k0: T, spring_constant_k1: T, standard: coordinate::Coordinate<T>) -> Self {
+        let epsilon = T::from_f64(1.0e-10);
+        let norm = standard.norm();
+        if norm <= T::one() - epsilon || norm >= T::one() + epsilon {
+            panic!("norm of standard_vector should be 1.0")
         }
         TorsionSpring {
             spring_constant_k0: spring_constant_k0,
@@ -30,19 +31,20 @@ impl TorsionSpring {
 
     pub fn force(
         &self,
-        base: coordinate::Coordinate,
-        center: coordinate::Coordinate,
-        tip: coordinate::Coordinate,
-        target_angle: f64,
-    ) -> (coordinate::Coordinate, coordinate::Coordinate) {
+        base: coordinate::Coordinate<T>,
+        center: coordinate::Coordinate<T>,
+        tip: coordinate::Coordinate<T>,
+        target_angle: Rad<T>,
+    ) -> (coordinate::Coordinate<T>, coordinate::Coordinate<T>) {
         // calculate torsion force applied on tip and base, so that Arg(base-center, center-tip) anti-clock-wise to standard_vector becomes target_angular.
         // force applied on tip and base is or symmetrical.
-        // base and tip are not symmetric, i.e. if you swap base and tip you should modify target_angular to (2*PI - original_target_angle).
-        // range of target_angle is [0, 2*PI], if it exceeds target_angle % 2*PI will be used.
+        // the angular discrepancy is always taken along the shortest arc, via Rad::normalize.
         let vec_bc = center - base;
         let vec_ct = tip - center;
-        let angle_diff = self.angle(vec_bc, vec_ct) - target_angle;
-        if angle_diff.abs() < EPSILON {
+        let angle_diff = (Rad::new(self.angle(vec_bc, vec_ct)) - target_angle)
+            .normalize()
+            .value();
+        if angle_diff.abs() < T::from_f64(EPSILON) {
             // take into account numeric error
             (
                 coordinate::Coordinate::zero(),
@@ -58,86 +60,194 @@ impl TorsionSpring {
         }
     }
 
+    /// Same as the two-somite force calculation above, plus the torque the joint
+    /// itself exerts on the center somite about `standard_vector`, so the bending
+    /// energy stored in the joint can also drive the center somite's rigid-body
+    /// rotation instead of only ever translating its neighbors.
     pub fn force_on_discrepancy(
         &self,
-        base: coordinate::Coordinate,
-        center: coordinate::Coordinate,
-        tip: coordinate::Coordinate,
-        discrepancy_angle_angle: f64,
-    ) -> (coordinate::Coordinate, coordinate::Coordinate) {
-        // calculate torsion force applied on tip and base, so that discrepancy_angle_angle becomse zero.
-        // discrepancy_angle is angle from actual position to target position.
+        base: coordinate::Coordinate<T>,
+        center: coordinate::Coordinate<T>,
+        tip: coordinate::Coordinate<T>,
+        discrepancy_angle: Rad<T>,
+    ) -> (coordinate::Coordinate<T>, coordinate::Coordinate<T>, coordinate::Coordinate<T>) {
+        // calculate torsion force applied on tip and base, so that discrepancy_angle becomes zero.
+        // discrepancy_angle is the (shortest-arc) angle from actual position to target position.
         // force applied on tip and base is or symmetrical.
-        // base and tip are not symmetric, i.e. if you swap base and tip you should modify target_angular to (2*PI - original_target_angle).
-        // range of target_angle is [0, 2*PI], if it exceeds target_angle % 2*PI will be used.
         let vec_bc = center - base;
         let vec_ct = tip - center;
-        if discrepancy_angle_angle < EPSILON {
+        let discrepancy_angle_angle = discrepancy_angle.normalize().value();
+        if discrepancy_angle_angle.abs() < T::from_f64(EPSILON) {
             // take into account numeric error
             (
                 coordinate::Coordinate::zero(),
                 coordinate::Coordinate::zero(),
+                coordinate::Coordinate::zero(),
             )
         } else {
+            let torque = self.standard_vector
+                * self.calculate_spring_constant(discrepancy_angle_angle)
+                * discrepancy_angle_angle;
             (
                 self.normal_vector(vec_ct) * self.calculate_spring_constant(discrepancy_angle_angle)
                     * discrepancy_angle_angle, // no minus since discrepancy_angle is from actual to target position
                 self.normal_vector(vec_bc) * self.calculate_spring_constant(discrepancy_angle_angle)
                     * discrepancy_angle_angle,
+                torque,
+            )
+        }
+    }
+
+    /// Resolve the joint's spring force toward `target_angle` given an
+    /// already-computed `current_angle` (callers that also need the angle for
+    /// differentiation, as `add_material_torsion_spring_forces` does, can reuse it
+    /// instead of recomputing it), plus `extra_torque` (e.g. angular damping) folded
+    /// into the torque returned about the center somite.
+    pub fn force_to_target_angle(
+        &self,
+        base: &coordinate::Coordinate<T>,
+        center: &coordinate::Coordinate<T>,
+        tip: &coordinate::Coordinate<T>,
+        current_angle: Rad<T>,
+        target_angle: T,
+        extra_torque: T,
+    ) -> (coordinate::Coordinate<T>, coordinate::Coordinate<T>, coordinate::Coordinate<T>) {
+        let vec_bc = *center - *base;
+        let vec_ct = *tip - *center;
+        let angle_diff = (current_angle - Rad::new(target_angle)).normalize().value();
+        let torque = self.standard_vector
+            * (-self.calculate_spring_constant(angle_diff) * angle_diff + extra_torque);
+        if angle_diff.abs() < T::from_f64(EPSILON) {
+            (
+                coordinate::Coordinate::zero(),
+                coordinate::Coordinate::zero(),
+                torque,
+            )
+        } else {
+            (
+                self.normal_vector(vec_ct) * -self.calculate_spring_constant(angle_diff) * angle_diff,
+                self.normal_vector(vec_bc) * -self.calculate_spring_constant(angle_diff) * angle_diff,
+                torque,
             )
         }
     }
 
     pub fn current_angle(
         &self,
-        base: coordinate::Coordinate,
-        center: coordinate::Coordinate,
-        tip: coordinate::Coordinate,
-    ) -> f64 {
+        base: coordinate::Coordinate<T>,
+        center: coordinate::Coordinate<T>,
+        tip: coordinate::Coordinate<T>,
+    ) -> Rad<T> {
         // 1.0 if current angle -> target angle is anti-clock-wise
         // -1.0 if current angle -> target angle is anti-clock-wise
         let vec_bc = center - base;
         let vec_ct = tip - center;
-        self.angle(vec_bc, vec_ct)
+        Rad::new(self.angle(vec_bc, vec_ct))
     }
 
-    fn calculate_spring_constant(&self, target_angle: f64) -> f64 {
+    fn calculate_spring_constant(&self, target_angle: T) -> T {
         self.spring_constant_k0 + self.spring_constant_k1 * target_angle.abs()
     }
 
-    fn normal_vector(&self, v: coordinate::Coordinate) -> coordinate::Coordinate {
+    fn normal_vector(&self, v: coordinate::Coordinate<T>) -> coordinate::Coordinate<T> {
         // anti-clock-wise orthogonal vector to v, whose norm is 1
         self.standard_vector.cross_product(self.project(v)) / self.project(v).norm()
     }
 
-    fn angle(&self, v1: coordinate::Coordinate, v2: coordinate::Coordinate) -> f64 {
+    fn angle(&self, v1: coordinate::Coordinate<T>, v2: coordinate::Coordinate<T>) -> T {
         // Arg(v1, v2) anti-clock-wise to the standard_vector
-        if self.sin(v1, v2) >= 0.0 {
+        if self.sin(v1, v2) >= T::zero() {
             self.cos(v1, v2).acos()
         } else {
             -self.cos(v1, v2).acos()
         }
     }
 
-    fn cos(&self, v1: coordinate::Coordinate, v2: coordinate::Coordinate) -> f64 {
+    fn cos(&self, v1: coordinate::Coordinate<T>, v2: coordinate::Coordinate<T>) -> T {
         let v1_ = self.project(v1);
         let v2_ = self.project(v2);
         v1_.inner_product(v2_) / (v1_.norm() * v2_.norm())
     }
 
-    fn sin(&self, v1: coordinate::Coordinate, v2: coordinate::Coordinate) -> f64 {
+    fn sin(&self, v1: coordinate::Coordinate<T>, v2: coordinate::Coordinate<T>) -> T {
         let v1_ = self.project(v1);
         let v2_ = self.project(v2);
         let cross = v1_.cross_product(v2_);
-        cross.norm() / (v1_.norm() * v2_.norm())
-            * cross.inner_product(self.standard_vector).signum()
+        cross.norm() / (v1_.norm() * v2_.norm()) * cross.inner_product(self.standard_vector).signum()
     }
 
-    fn project(&self, v: coordinate::Coordinate) -> coordinate::Coordinate {
+    fn project(&self, v: coordinate::Coordinate<T>) -> coordinate::Coordinate<T> {
         v - self.standard_vector * self.standard_vector.inner_product(v)
     }
 }
 
+/// Wraps a `TorsionSpring` with discrete PID actuation so it can hold a target angle
+/// against steady-state disturbances (gravity, friction) instead of only reacting
+/// proportionally. `integral_clamp` bounds `integrator_state` to prevent wind-up, and
+/// `rom_clamp` bounds the commanded angle to the joint's range of motion.
+pub struct TorsionSpringController {
+    torsion_spring: TorsionSpring,
+    ki: f64,
+    kd: f64,
+    eta: f64,
+    integral_clamp: f64,
+    rom_clamp: f64,
+    integrator_state: cell::Cell<f64>,
+    previous_angle: cell::Cell<Option<f64>>,
+}
+
+impl TorsionSpringController {
+    pub fn new(
+        torsion_spring: TorsionSpring,
+        ki: f64,
+        kd: f64,
+        eta: f64,
+        integral_clamp: f64,
+        rom_clamp: f64,
+    ) -> Self {
+        TorsionSpringController {
+            torsion_spring: torsion_spring,
+            ki: ki,
+            kd: kd,
+            eta: eta,
+            integral_clamp: integral_clamp,
+            rom_clamp: rom_clamp,
+            integrator_state: cell::Cell::new(0.),
+            previous_angle: cell::Cell::new(None),
+        }
+    }
+
+    /// Advance the controller by one step of `dt` given the joint's `current_angle`
+    /// and `target_angle`, and return the tip/base forces to apply.
+    pub fn force(
+        &self,
+        base: coordinate::Coordinate,
+        center: coordinate::Coordinate,
+        tip: coordinate::Coordinate,
+        current_angle: f64,
+        target_angle: f64,
+        dt: f64,
+    ) -> (coordinate::Coordinate, coordinate::Coordinate) {
+        let error = target_angle - current_angle;
+        let integrator_state = (self.eta * self.integrator_state.get() + self.ki * error * dt)
+            .max(-self.integral_clamp)
+            .min(self.integral_clamp);
+        self.integrator_state.set(integrator_state);
+
+        let derivative = self.previous_angle
+            .get()
+            .and_then(|previous_angle| calculations::differentiate(previous_angle, current_angle, dt))
+            .unwrap_or(0.);
+        self.previous_angle.set(Some(current_angle));
+
+        let command = (target_angle + integrator_state - self.kd * derivative)
+            .max(-self.rom_clamp)
+            .min(self.rom_clamp);
+
+        self.torsion_spring.force(base, center, tip, Rad::new(command))
+    }
+}
+
 impl fmt::Display for TorsionSpring {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
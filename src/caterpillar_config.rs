@@ -1,5 +1,12 @@
+extern crate serde;
+extern crate serde_json;
+
 use std::fmt;
+use std::fs;
+use std::io::Read;
+use coordinate;
 
+#[derive(Serialize, Deserialize)]
 pub struct Config {
     pub time_delta: f64,
     pub somite_mass: f64,
@@ -16,6 +23,65 @@ pub struct Config {
     pub vertical_ts_k: f64,
     pub realtime_tunable_ts_rom: f64,
     pub friction_coeff: f64,
+    pub contact_k: f64,
+    pub restitution_coeff: f64,
+    pub drag_u_ref: f64,
+    pub drag_z_ref: f64,
+    pub drag_z0: f64,
+    pub drag_direction: coordinate::Coordinate,
+    pub c_drag: f64,
+    // opt-in swept ground-contact test for somites fast enough to tunnel through the
+    // ground within a single time_delta; the cheaper discrete test is used otherwise
+    pub continuous_ground_contact: bool,
+}
+
+impl Config {
+    /// Parse a `Config` from a JSON file on disk, so physical parameters (masses,
+    /// stiffnesses, `friction_coeff`, ...) can be swept from Python without
+    /// recompiling the extension, instead of living in the compile-time `CONFIG`.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let mut file = fs::File::open(path)
+            .map_err(|e| format!("failed to open config file {}: {}", path, e))?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| format!("failed to read config file {}: {}", path, e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("failed to parse config file {}: {}", path, e))
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            somite_mass: 0.5,
+            somite_radius: 0.35,
+            normal_angular_velocity: ::std::f64::consts::PI,
+            rts_max_natural_length: 0.7,
+            rts_k: 100.0,
+            rts_c: 1.0,
+            rts_amp: 0.3,
+            sp_natural_length: 0.7,
+            sp_k: 80.0,
+            dp_c: 10.0,
+            horizon_ts_k: 10.,
+            vertical_ts_k: 200.,
+            realtime_tunable_ts_rom: ::std::f64::consts::PI / 6.,
+            friction_coeff: 10.0,
+            time_delta: 0.01,
+            contact_k: 500.0,
+            restitution_coeff: 0.3,
+            drag_u_ref: 0.0,
+            drag_z_ref: 1.0,
+            drag_z0: 0.01,
+            drag_direction: coordinate::Coordinate {
+                x: 1.,
+                y: 0.,
+                z: 0.,
+            },
+            c_drag: 0.0,
+            continuous_ground_contact: false,
+        }
+    }
 }
 
 impl fmt::Display for Config {
@@ -42,6 +108,16 @@ impl fmt::Display for Config {
              vertical k: {} N/rad\n\
              [realtime tunable torsion spring]
              range of motion: {} rad\n\
+             [self collision]\n\
+             contact k: {} N/m\n\
+             [ground contact]\n\
+             restitution coefficient: {}\n\
+             continuous (swept) contact: {}\n\
+             [drag field]\n\
+             reference speed: {} m/s at {} m\n\
+             roughness length: {} m\n\
+             direction: {}\n\
+             drag coefficient: {} Ns/m\n\
              [simulation]\n\
              one time step: {} s",
             self.somite_mass,
@@ -58,6 +134,14 @@ impl fmt::Display for Config {
             self.horizon_ts_k,
             self.vertical_ts_k,
             self.realtime_tunable_ts_rom,
+            self.contact_k,
+            self.restitution_coeff,
+            self.continuous_ground_contact,
+            self.drag_u_ref,
+            self.drag_z_ref,
+            self.drag_z0,
+            self.drag_direction,
+            self.c_drag,
             self.time_delta,
         )
     }
@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+use coordinate::Coordinate;
+use somite::Somite;
+
+/// A sphere bound around a somite, used for the self-collision broad/narrow phase.
+pub struct SphereBounds {
+    pub center: Coordinate,
+    pub radius: f64,
+}
+
+impl SphereBounds {
+    pub fn new(center: Coordinate, radius: f64) -> Self {
+        SphereBounds {
+            center: center,
+            radius: radius,
+        }
+    }
+
+    pub fn intersects(&self, other: &SphereBounds) -> bool {
+        (other.center - self.center).norm() <= self.radius + other.radius
+    }
+}
+
+type Cell = (i64, i64, i64);
+
+fn cell_of(c: Coordinate, cell_size: f64) -> Cell {
+    (
+        (c.x / cell_size).floor() as i64,
+        (c.y / cell_size).floor() as i64,
+        (c.z / cell_size).floor() as i64,
+    )
+}
+
+/// Bucket somites into a uniform spatial hash and return the set of (i, j) pairs, i < j,
+/// whose sphere bounds overlap. Directly-connected neighbor somites are excluded, since
+/// the structural springs already keep them at the right distance.
+pub fn find_colliding_pairs(somites: &[Somite]) -> Vec<(usize, usize)> {
+    let max_radius = somites.iter().fold(0.0_f64, |acc, s| acc.max(s.radius));
+    if max_radius <= 0. {
+        return Vec::new();
+    }
+    let cell_size = 2. * max_radius;
+
+    let mut grid: HashMap<Cell, Vec<usize>> = HashMap::new();
+    for (i, s) in somites.iter().enumerate() {
+        grid.entry(cell_of(s.get_position(), cell_size))
+            .or_insert_with(Vec::new)
+            .push(i);
+    }
+
+    let mut pairs = HashSet::new();
+    for (&(cx, cy, cz), indices) in grid.iter() {
+        for dx in -1..2 {
+            for dy in -1..2 {
+                for dz in -1..2 {
+                    let neighbors = match grid.get(&(cx + dx, cy + dy, cz + dz)) {
+                        Some(n) => n,
+                        None => continue,
+                    };
+                    for &i in indices {
+                        for &j in neighbors {
+                            if i == j || i + 1 == j || j + 1 == i {
+                                continue;
+                            }
+                            let (lo, hi) = if i < j { (i, j) } else { (j, i) };
+                            let bi = SphereBounds::new(somites[lo].get_position(), somites[lo].radius);
+                            let bj = SphereBounds::new(somites[hi].get_position(), somites[hi].radius);
+                            if bi.intersects(&bj) {
+                                pairs.insert((lo, hi));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pairs.into_iter().collect()
+}
+
+/// Penalty force pushing two overlapping somites apart. Returns the force applied to the
+/// somite at `hi`; the somite at `lo` receives the opposite reaction.
+pub fn contact_force(somites: &[Somite], lo: usize, hi: usize, contact_k: f64) -> Coordinate {
+    let c1 = somites[lo].get_position();
+    let c2 = somites[hi].get_position();
+    let dist = (c2 - c1).norm();
+    if dist <= 0. {
+        return Coordinate::zero();
+    }
+    let penetration = (somites[lo].radius + somites[hi].radius) - dist;
+    if penetration <= 0. {
+        return Coordinate::zero();
+    }
+    let n = (c2 - c1) / dist;
+    n * contact_k * penetration
+}
+
+/// Resolve all self-collisions this step by adding equal-and-opposite penalty forces
+/// into `forces`, a per-somite force accumulator the same shape as `somites`.
+pub fn resolve_self_collisions(somites: &[Somite], contact_k: f64, forces: &mut Vec<Coordinate>) {
+    for (lo, hi) in find_colliding_pairs(somites) {
+        let f = contact_force(somites, lo, hi, contact_k);
+        forces[lo] -= f;
+        forces[hi] += f;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sphere_bounds_intersects() {
+        let a = SphereBounds::new(Coordinate::zero(), 1.);
+        let b = SphereBounds::new(
+            Coordinate {
+                x: 1.5,
+                y: 0.,
+                z: 0.,
+            },
+            1.,
+        );
+        assert!(a.intersects(&b));
+
+        let c = SphereBounds::new(
+            Coordinate {
+                x: 3.,
+                y: 0.,
+                z: 0.,
+            },
+            1.,
+        );
+        assert!(!a.intersects(&c));
+    }
+
+    #[test]
+    fn test_find_colliding_pairs_excludes_neighbors() {
+        let somites = vec![
+            Somite::new_still_somite(1., Coordinate { x: 0., y: 0., z: 1. }),
+            Somite::new_still_somite(1., Coordinate { x: 1., y: 0., z: 1. }),
+        ];
+        // somites 0 and 1 overlap, but they are direct neighbors so should be ignored
+        assert!(find_colliding_pairs(&somites).is_empty());
+    }
+
+    #[test]
+    fn test_find_colliding_pairs_detects_fold_back() {
+        let somites = vec![
+            Somite::new_still_somite(1., Coordinate { x: 0., y: 0., z: 1. }),
+            Somite::new_still_somite(1., Coordinate { x: 3., y: 0., z: 1. }),
+            Somite::new_still_somite(1., Coordinate { x: 0.5, y: 0., z: 1. }),
+        ];
+        // somite 0 and somite 2 are not chain neighbors but overlap in space
+        assert_eq!(find_colliding_pairs(&somites), vec![(0, 2)]);
+    }
+}
@@ -0,0 +1,49 @@
+use coordinate;
+
+/// Environmental drag field modeling a height-varying ambient flow, following the
+/// atmospheric-boundary-layer log law: flow speed grows logarithmically with height
+/// above a roughness length `z0`, so somites close to the substrate feel weaker flow.
+pub struct DragField {
+    u_star: f64,
+    z0: f64,
+    kappa: f64,
+    flow_direction: coordinate::Coordinate, // unit vector
+    c_drag: f64,
+}
+
+impl DragField {
+    /// `u_ref` is the reference flow speed at height `z_ref`, `z0` is the roughness
+    /// length, `flow_direction` need not be normalized, and `kappa` is the von-Kármán
+    /// constant (≈0.41).
+    pub fn new(
+        u_ref: f64,
+        z_ref: f64,
+        z0: f64,
+        flow_direction: coordinate::Coordinate,
+        kappa: f64,
+        c_drag: f64,
+    ) -> Self {
+        let u_star = kappa * u_ref / ((z_ref + z0) / z0).ln();
+        DragField {
+            u_star: u_star,
+            z0: z0,
+            kappa: kappa,
+            flow_direction: flow_direction / flow_direction.norm(),
+            c_drag: c_drag,
+        }
+    }
+
+    /// Local flow speed at height `z`, following the log law.
+    pub fn flow_speed(&self, z: f64) -> f64 {
+        (self.u_star / self.kappa) * ((z + self.z0) / self.z0).ln()
+    }
+
+    fn flow_velocity(&self, z: f64) -> coordinate::Coordinate {
+        self.flow_direction * self.flow_speed(z)
+    }
+
+    /// Drag force on a somite moving at `verocity` at height `z`.
+    pub fn force(&self, verocity: coordinate::Coordinate, z: f64) -> coordinate::Coordinate {
+        (verocity - self.flow_velocity(z)) * -self.c_drag
+    }
+}
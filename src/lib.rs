@@ -7,7 +7,7 @@ extern crate serde_derive;
 use std::f64;
 use std::cell;
 use std::collections;
-use cpython::{PyObject, PyResult, PyString, PyTuple, Python, ToPyObject};
+use cpython::{PyErr, PyObject, PyResult, PyString, PyTuple, Python, ToPyObject};
 
 mod phase_oscillator;
 mod somite;
@@ -17,81 +17,44 @@ mod dumper;
 mod caterpillar_config;
 mod coordinate;
 mod simulation_export;
+mod collision;
+mod wrench;
+mod drag;
 
 use somite::Somite;
 
 const GRAVITATIONAL_ACCELERATION: f64 = 9.8065;
-const CONFIG: caterpillar_config::Config = caterpillar_config::Config {
-    somite_mass: 0.5,
-    somite_radius: 0.35,
-    normal_angular_velocity: std::f64::consts::PI,
-    rts_max_natural_length: 0.7,
-    rts_k: 100.0,
-    rts_c: 1.0,
-    rts_amp: 0.3,
-    sp_natural_length: 0.7,
-    sp_k: 80.0,
-    dp_c: 10.0,
-    horizon_ts_k: 10.,
-    vertical_ts_k: 200.,
-    realtime_tunable_ts_rom: f64::consts::PI / 6.,
-    friction_coeff: 10.0,
-    time_delta: 0.01,
-};
-
+// caps how much normal velocity a single ground-contact impulse may resolve in one step
+const GROUND_CONTACT_V_CAP: f64 = 20.0;
+const VON_KARMAN_CONSTANT: f64 = 0.41;
 py_module_initializer!(caterpillar, initcaterpillar, PyInit_caterpillar, |py, m| {
     try!(m.add(
         py,
         "__doc__",
         "This is Rust implementation of caterpillar simulater."
     ));
-    try!(m.add(py, "print_config", py_fn!(py, print_caterpillar_config())));
     try!(m.add_class::<Caterpillar>(py));
     Ok(())
 });
 
-fn print_caterpillar_config(_: Python) -> PyResult<String> {
-    Ok(format!("{}", CONFIG))
-}
-
 py_class!(class Caterpillar |py| {
+    data config: caterpillar_config::Config;
     data somites: Vec<Somite>;
     data simulation_protocol: simulation_export::SimulationProc;
     data frame_count: cell::Cell<u32>;
     data temp_forces: Vec<cell::Cell<coordinate::Coordinate>>;
     data oscillators: collections::HashMap<u32, cell::RefCell<phase_oscillator::PhaseOscillator>>;
+    data couplings: cell::RefCell<phase_oscillator::CouplingNetwork>;
     def __new__(_cls, somite_number: usize, somites_to_set_oscillater: &PyTuple) -> PyResult<Caterpillar> {
-        let somites = (0..somite_number).map(|i| {
-            Somite::new_still_somite(
-                CONFIG.somite_radius,
-                CONFIG.somite_mass,
-                coordinate::Coordinate{x: (i as f64)*2.*CONFIG.somite_radius, y: 0., z: CONFIG.somite_radius}
-            )
-        }).collect::<Vec<somite::Somite>>();
-
-        let simulation_protocol = simulation_export::SimulationProc::new(
-            somites.iter().enumerate().map(|(i, s)| {
-                simulation_export::Object{id: format!("_somite_{}", i), rad: CONFIG.somite_radius, pos: s.get_position().to_tuple()}
-            }).collect::<Vec<simulation_export::Object>>()
-        );
-
-        let temp_forces = (0..somite_number).map(|_| {
-            cell::Cell::new(coordinate::Coordinate::zero())
-        }).collect();
-
-        let mut oscillators = collections::HashMap::<u32, cell::RefCell<phase_oscillator::PhaseOscillator>>::new();
-        for somite_id in somites_to_set_oscillater.iter(py) {
-            oscillators.insert(somite_id.extract::<u32>(py).unwrap(), cell::RefCell::<phase_oscillator::PhaseOscillator>::new(phase_oscillator::PhaseOscillator::new()));
-        }
-
-        Caterpillar::create_instance(
-            py,
-            somites,
-            simulation_protocol,
-            cell::Cell::<u32>::new(0),
-            temp_forces,
-            oscillators,
-        )
+        Self::build(py, somite_number, somites_to_set_oscillater, caterpillar_config::Config::default())
+    }
+    @staticmethod
+    def from_config(somite_number: usize, somites_to_set_oscillater: &PyTuple, config_path: String) -> PyResult<Caterpillar> {
+        let config = match caterpillar_config::Config::from_file(&config_path) {
+            Ok(config) => config,
+            Err(message) => return Err(PyErr::new::<PyString, _>(py, &message)),
+        };
+        Self::build(py, somite_number, somites_to_set_oscillater, config)
     }
     def show_positions(&self) -> PyResult<PyString> {
         let mut position_report = "Positions of somites\n".to_string();
@@ -105,7 +68,7 @@ py_class!(class Caterpillar |py| {
         Ok(py.None())
     }
     def print_config(&self) -> PyResult<String> {
-        Ok(CONFIG.to_string())
+        Ok(self.config(py).to_string())
     }
     def center_of_mass(&self) -> PyResult<PyTuple> {
         let center = self.calculate_center_of_mass(py);
@@ -121,8 +84,12 @@ py_class!(class Caterpillar |py| {
         Ok(py.None())
     }
     def step(&self) -> PyResult<PyObject> {
-        for (_, oscillator) in self.oscillators(py) {
-            oscillator.borrow_mut().step(CONFIG.normal_angular_velocity, CONFIG.time_delta);
+        let config = self.config(py);
+        let phases = self.snapshot_oscillator_phases(py);
+        let couplings = self.couplings(py).borrow();
+        for (&id, oscillator) in self.oscillators(py) {
+            let phase_speed = config.normal_angular_velocity + couplings.coupling_term(id, &phases);
+            oscillator.borrow_mut().step(phase_speed, config.time_delta);
         }
         self.update_state(py);
         Ok(py.None())
@@ -131,17 +98,101 @@ py_class!(class Caterpillar |py| {
         if feedbacks.len(py) != self.oscillators(py).len() {
             panic!("number of elements in feedbacks and oscillator controllers are inconsistent");
         }
+        let config = self.config(py);
+        let phases = self.snapshot_oscillator_phases(py);
+        let couplings = self.couplings(py).borrow();
         let mut iter = feedbacks.iter(py);
-        for (_, oscillator) in self.oscillators(py) {
-            oscillator.borrow_mut().step(CONFIG.normal_angular_velocity + iter.next().unwrap().extract::<f64>(py).unwrap(), CONFIG.time_delta);
+        for (&id, oscillator) in self.oscillators(py) {
+            let phase_speed = config.normal_angular_velocity
+                + iter.next().unwrap().extract::<f64>(py).unwrap()
+                + couplings.coupling_term(id, &phases);
+            oscillator.borrow_mut().step(phase_speed, config.time_delta);
         }
 
         self.update_state(py);
         Ok(py.None())
     }
+    def set_coupling(&self, i: u32, j: u32, weight: f64, phase_bias: f64) -> PyResult<PyObject> {
+        self.couplings(py).borrow_mut().set_coupling(i, j, weight, phase_bias);
+        Ok(py.None())
+    }
+    /// Convenience over repeated `set_coupling` calls: wire every adjacent pair of
+    /// oscillators along the body into a nearest-neighbor CPG chain with a single
+    /// `weight`/`phase_bias`, so a posterior-to-anterior contraction wave can
+    /// self-organize without hand-tuning each segment's phase. Couples each anterior
+    /// (lower id) oscillator to its posterior (higher id) neighbor; pass
+    /// `bidirectional = true` to also couple back the other way instead of pure
+    /// feed-forward.
+    def set_chain_coupling(&self, weight: f64, phase_bias: f64, bidirectional: bool) -> PyResult<PyObject> {
+        let mut ids = self.oscillators(py).keys().cloned().collect::<Vec<u32>>();
+        ids.sort();
+        let mut couplings = self.couplings(py).borrow_mut();
+        for pair in ids.windows(2) {
+            let (anterior, posterior) = (pair[0], pair[1]);
+            couplings.set_coupling(anterior, posterior, weight, phase_bias);
+            if bidirectional {
+                couplings.set_coupling(posterior, anterior, weight, phase_bias);
+            }
+        }
+        Ok(py.None())
+    }
 });
 
 impl Caterpillar {
+    /// Shared construction path for `__new__` and `from_config`: build the somite
+    /// chain, simulation protocol and oscillators from a per-instance `Config` rather
+    /// than the old compile-time `CONFIG` constant.
+    fn build(
+        py: Python,
+        somite_number: usize,
+        somites_to_set_oscillater: &PyTuple,
+        config: caterpillar_config::Config,
+    ) -> PyResult<Caterpillar> {
+        let somites = (0..somite_number).map(|i| {
+            Somite::new_still_somite(
+                config.somite_radius,
+                config.somite_mass,
+                coordinate::Coordinate{x: (i as f64)*2.*config.somite_radius, y: 0., z: config.somite_radius}
+            )
+        }).collect::<Vec<somite::Somite>>();
+
+        let simulation_protocol = simulation_export::SimulationProc::new(
+            somites.iter().enumerate().map(|(i, s)| {
+                simulation_export::Object{id: format!("_somite_{}", i), rad: config.somite_radius, pos: s.get_position().to_tuple()}
+            }).collect::<Vec<simulation_export::Object>>()
+        );
+
+        let temp_forces = (0..somite_number).map(|_| {
+            cell::Cell::new(coordinate::Coordinate::zero())
+        }).collect();
+
+        let mut oscillators = collections::HashMap::<u32, cell::RefCell<phase_oscillator::PhaseOscillator>>::new();
+        for somite_id in somites_to_set_oscillater.iter(py) {
+            oscillators.insert(somite_id.extract::<u32>(py).unwrap(), cell::RefCell::<phase_oscillator::PhaseOscillator>::new(phase_oscillator::PhaseOscillator::new()));
+        }
+
+        Caterpillar::create_instance(
+            py,
+            config,
+            somites,
+            simulation_protocol,
+            cell::Cell::<u32>::new(0),
+            temp_forces,
+            oscillators,
+            cell::RefCell::new(phase_oscillator::CouplingNetwork::new()),
+        )
+    }
+
+    /// Read every oscillator's current phase before stepping any of them, so that
+    /// coupling terms are computed from a consistent snapshot rather than a mix of
+    /// old and already-advanced phases.
+    fn snapshot_oscillator_phases(&self, py: Python) -> collections::HashMap<u32, f64> {
+        self.oscillators(py)
+            .iter()
+            .map(|(&id, oscillator)| (id, oscillator.borrow().get_phase()))
+            .collect()
+    }
+
     fn calculate_center_of_mass(&self, py: Python) -> coordinate::Coordinate {
         let mut sum = coordinate::Coordinate {
             x: 0.,
@@ -156,6 +207,14 @@ impl Caterpillar {
 
     fn update_state(&self, py: Python) {
         self.update_somite_positions(py);
+        let config = self.config(py);
+        for s in self.somites(py) {
+            if config.continuous_ground_contact {
+                s.resolve_swept_ground_contact(config.restitution_coeff, GROUND_CONTACT_V_CAP);
+            } else {
+                s.resolve_ground_contact(config.restitution_coeff, GROUND_CONTACT_V_CAP);
+            }
+        }
         let new_forces = self.calculate_force_on_somites(py);
         self.update_somite_verocities(py, &new_forces);
         self.update_somite_forces(py, &new_forces);
@@ -185,9 +244,11 @@ impl Caterpillar {
     fn update_somite_positions(&self, py: Python) {
         // update somite positions based on Velret's method
         // x_{t+1} = x_{t} + \delta t v_{t} + 0.5 \delta t^2 f_{t, x_t}
+        let time_delta = self.config(py).time_delta;
         for s in self.somites(py) {
-            let new_position = s.get_position() + s.get_verocity() * CONFIG.time_delta
-                + s.get_force() * 0.5 * CONFIG.time_delta.powi(2) / s.mass;
+            let new_position = s.get_position() + s.get_verocity() * time_delta
+                + s.get_force() * 0.5 * time_delta.powi(2) / s.mass;
+            s.set_previous_position(s.get_position());
             s.set_position(new_position);
         }
     }
@@ -195,9 +256,10 @@ impl Caterpillar {
     fn update_somite_verocities(&self, py: Python, new_forces: &Vec<coordinate::Coordinate>) {
         // update somite verocities based on Velret's method
         // v_{t+1} = v_{t} +  \delta \frac{t (f_{t, x_t} + f_{t+1, x_{t+1}})}{2}
+        let time_delta = self.config(py).time_delta;
         for (i, s) in self.somites(py).iter().enumerate() {
             let mut new_verocity = s.get_verocity()
-                + (s.get_force() + new_forces[i]) * 0.5 * CONFIG.time_delta / s.mass;
+                + (s.get_force() + new_forces[i]) * 0.5 * time_delta / s.mass;
             if s.is_on_ground() {
                 new_verocity.z = new_verocity.z.max(0.);
             }
@@ -214,6 +276,7 @@ impl Caterpillar {
     fn calculate_force_on_somites(&self, py: Python) -> Vec<coordinate::Coordinate> {
         // calculate force from friction, tension, dumping, etc.
         // collect temporary force applied on somites and reset temp_forces instance variable
+        let config = self.config(py);
         let mut new_forces = self.temp_forces(py)
             .iter()
             .map(|f| f.replace(coordinate::Coordinate::zero()))
@@ -227,14 +290,14 @@ impl Caterpillar {
         // frictional force against ground
         for (i, s) in self.somites(py).iter().enumerate() {
             if s.is_on_ground() {
-                new_forces[i].x += s.get_verocity().x * -CONFIG.friction_coeff;
-                new_forces[i].y += s.get_verocity().y * -CONFIG.friction_coeff;
+                new_forces[i].x += s.get_verocity().x * -config.friction_coeff;
+                new_forces[i].y += s.get_verocity().y * -config.friction_coeff;
             }
         }
 
         // spring and dumper effects
-        let sp = spring::Spring::new(CONFIG.sp_k, CONFIG.sp_natural_length);
-        let dp = dumper::Dumper::new(CONFIG.dp_c);
+        let sp = spring::Spring::new(config.sp_k, config.sp_natural_length);
+        let dp = dumper::Dumper::new(config.dp_c);
         for i in 0..(self.somites(py).len() - 1) {
             new_forces[i] += sp.force(
                 self.somites(py)[i + 1].get_position(),
@@ -257,7 +320,7 @@ impl Caterpillar {
 
         // torsion spring
         let vertical_ts = torsion_spring::TorsionSpring::new(
-            CONFIG.vertical_ts_k,
+            config.vertical_ts_k,
             coordinate::Coordinate {
                 x: 0.,
                 y: 1.,
@@ -265,7 +328,7 @@ impl Caterpillar {
             },
         );
         let horizon_ts = torsion_spring::TorsionSpring::new(
-            CONFIG.horizon_ts_k,
+            config.horizon_ts_k,
             coordinate::Coordinate {
                 x: 0.,
                 y: 0.,
@@ -275,6 +338,7 @@ impl Caterpillar {
         for i in 0..(self.somites(py).len() - 2) {
             // torsion spring at i+1 th somite
             let target_angle = Self::phase2torsion_spring_target_angle(
+                config,
                 self.oscillators(py)
                     .get(&(i as u32 + 1))
                     .unwrap()
@@ -341,6 +405,22 @@ impl Caterpillar {
             );
         }
 
+        // push apart any somites (not directly connected) whose bodies overlap
+        collision::resolve_self_collisions(self.somites(py), config.contact_k, &mut new_forces);
+
+        // environmental drag from a height-varying ambient flow (log-law boundary layer)
+        let drag_field = drag::DragField::new(
+            config.drag_u_ref,
+            config.drag_z_ref,
+            config.drag_z0,
+            config.drag_direction,
+            VON_KARMAN_CONSTANT,
+            config.c_drag,
+        );
+        for (i, s) in self.somites(py).iter().enumerate() {
+            new_forces[i] += drag_field.force(s.get_verocity(), s.get_position().z);
+        }
+
         // mask negative z force is a somite is on ground
         self.mask_force_on_landing(py, new_forces)
     }
@@ -358,7 +438,7 @@ impl Caterpillar {
         forces
     }
 
-    fn phase2torsion_spring_target_angle(phase: f64) -> f64 {
-        CONFIG.realtime_tunable_ts_rom * phase.sin()
+    fn phase2torsion_spring_target_angle(config: &caterpillar_config::Config, phase: f64) -> f64 {
+        config.realtime_tunable_ts_rom * phase.sin()
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Copy, Clone)]
@@ -26,3 +27,43 @@ impl fmt::Display for PhaseOscillator {
         write!(f, "PhaseOscillator",)
     }
 }
+
+/// Coupling terms between oscillators in a CPG network, keyed by the ordered pair
+/// `(i, j)` whose term is added to oscillator `i`'s phase update. Following the
+/// continuous coupled-oscillator model, the phase update becomes
+/// `dphi_i/dt = omega_i + sum_j w_ij * sin(phi_j - phi_i - beta_ij)`, so the whole
+/// network can organize into traveling waves instead of every oscillator advancing
+/// independently.
+pub struct CouplingNetwork {
+    // (i, j) -> (weight, phase bias)
+    couplings: HashMap<(u32, u32), (f64, f64)>,
+}
+
+impl CouplingNetwork {
+    pub fn new() -> Self {
+        CouplingNetwork {
+            couplings: HashMap::new(),
+        }
+    }
+
+    pub fn set_coupling(&mut self, i: u32, j: u32, weight: f64, phase_bias: f64) {
+        self.couplings.insert((i, j), (weight, phase_bias));
+    }
+
+    /// Sum of `w_ij * sin(phi_j - phi_i - beta_ij)` over every coupling registered
+    /// for oscillator `i`, given a snapshot of every oscillator's current phase.
+    pub fn coupling_term(&self, i: u32, phases: &HashMap<u32, f64>) -> f64 {
+        let phase_i = match phases.get(&i) {
+            Some(phase) => *phase,
+            None => return 0.,
+        };
+        self.couplings
+            .iter()
+            .filter(|&(&(from, _), _)| from == i)
+            .map(|(&(_, j), &(weight, phase_bias))| match phases.get(&j) {
+                Some(phase_j) => weight * (phase_j - phase_i - phase_bias).sin(),
+                None => 0.,
+            })
+            .sum()
+    }
+}
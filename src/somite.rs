@@ -7,6 +7,14 @@ pub struct Somite {
     pub verocity: cell::Cell<coordinate::Coordinate>,
     pub force: cell::Cell<coordinate::Coordinate>,
     pub radius: f64,
+    // rotational state; orientation is tracked as an accumulated rotation vector
+    // (axis * angle) since somites otherwise behave as point masses with no attitude
+    pub orientation: cell::Cell<coordinate::Coordinate>,
+    pub angular_velocity: cell::Cell<coordinate::Coordinate>,
+    pub torque: cell::Cell<coordinate::Coordinate>,
+    pub moment_of_inertia: f64,
+    // position before the last integration step, used by the swept ground-contact test
+    pub previous_position: cell::Cell<coordinate::Coordinate>,
 }
 
 impl fmt::Display for Somite {
@@ -36,6 +44,13 @@ impl Somite {
                 z: 0.,
             }),
             radius: radius,
+            orientation: cell::Cell::new(coordinate::Coordinate::zero()),
+            angular_velocity: cell::Cell::new(coordinate::Coordinate::zero()),
+            torque: cell::Cell::new(coordinate::Coordinate::zero()),
+            // sphere moment of inertia I = 0.4 * m * r^2, assuming unit mass since
+            // Somite does not yet carry its own mass field
+            moment_of_inertia: 0.4 * radius.powi(2),
+            previous_position: cell::Cell::new(position),
         }
     }
 
@@ -82,4 +97,93 @@ impl Somite {
             coordinate::Coordinate::zero()
         }
     }
+
+    pub fn set_torque(&self, torque: coordinate::Coordinate) {
+        self.torque.set(torque);
+    }
+
+    pub fn get_torque(&self) -> coordinate::Coordinate {
+        self.torque.get()
+    }
+
+    pub fn get_orientation(&self) -> coordinate::Coordinate {
+        self.orientation.get()
+    }
+
+    pub fn get_angular_velocity(&self) -> coordinate::Coordinate {
+        self.angular_velocity.get()
+    }
+
+    pub fn is_on_ground(&self) -> bool {
+        self.position.get().z <= self.radius
+    }
+
+    pub fn set_previous_position(&self, position: coordinate::Coordinate) {
+        self.previous_position.set(position);
+    }
+
+    pub fn get_previous_position(&self) -> coordinate::Coordinate {
+        self.previous_position.get()
+    }
+
+    /// Resolve penetration into the ground with an impulse-based restitution response:
+    /// if the somite is moving into the ground, reflect its normal velocity scaled by
+    /// `restitution_coeff` (clamped to `v_cap` to keep stiff impacts stable), then snap
+    /// the somite back to `z = radius` to remove residual penetration.
+    pub fn resolve_ground_contact(&self, restitution_coeff: f64, v_cap: f64) {
+        let pos = self.position.get();
+        if pos.z >= self.radius {
+            return;
+        }
+
+        let v = self.verocity.get();
+        if v.z < 0. {
+            let v_rel = v.z.max(-v_cap);
+            let dv = -(1. + restitution_coeff) * v_rel;
+            self.verocity.set(coordinate::Coordinate {
+                x: v.x,
+                y: v.y,
+                z: v.z + dv,
+            });
+        }
+
+        self.position.set(coordinate::Coordinate {
+            x: pos.x,
+            y: pos.y,
+            z: self.radius,
+        });
+    }
+
+    /// Swept (continuous-time) counterpart of `resolve_ground_contact`, for somites
+    /// moving fast enough to tunnel through the contact plane within a single step:
+    /// if the segment from `previous_position` to the current position crosses
+    /// `z = radius`, solve for the fractional time of impact, snap the somite to the
+    /// contact point, and reflect/zero its normal velocity there.
+    pub fn resolve_swept_ground_contact(&self, restitution_coeff: f64, v_cap: f64) {
+        let prev = self.previous_position.get();
+        let cur = self.position.get();
+
+        if !(prev.z - self.radius > 0. && cur.z - self.radius <= 0.) {
+            self.resolve_ground_contact(restitution_coeff, v_cap);
+            return;
+        }
+
+        let t = (prev.z - self.radius) / (prev.z - cur.z);
+        let contact_point = prev + (cur - prev) * t;
+
+        let v = self.verocity.get();
+        let v_rel = v.z.max(-v_cap);
+        let dv = -(1. + restitution_coeff) * v_rel;
+        self.verocity.set(coordinate::Coordinate {
+            x: v.x,
+            y: v.y,
+            z: v.z + dv,
+        });
+
+        self.position.set(coordinate::Coordinate {
+            x: contact_point.x,
+            y: contact_point.y,
+            z: self.radius,
+        });
+    }
 }
@@ -1,3 +1,4 @@
+use std::f64;
 use coordinate;
 
 const EPSILON: f64 = 1.0e-5;
@@ -32,3 +33,167 @@ impl Spring {
         }
     }
 }
+
+/// BendSpring penalizes curvature across three consecutive somites, restoring the
+/// angle formed at `b` (by `a-b` and `c-b`) toward a rest angle.
+pub struct BendSpring {
+    spring_constant: f64,
+    rest_angle: f64,
+}
+
+impl BendSpring {
+    pub fn new(spring_constant: f64, rest_angle: f64) -> Self {
+        BendSpring {
+            spring_constant: spring_constant,
+            rest_angle: rest_angle,
+        }
+    }
+
+    /// Returns the forces to apply on `a` and `c`; `b` receives the negated sum of
+    /// both (the reaction), same convention as the torsion spring's base/tip forces.
+    pub fn force(
+        &self,
+        a: coordinate::Coordinate,
+        b: coordinate::Coordinate,
+        c: coordinate::Coordinate,
+    ) -> (coordinate::Coordinate, coordinate::Coordinate) {
+        let v1 = a - b;
+        let v2 = c - b;
+        let n1 = v1.norm();
+        let n2 = v2.norm();
+        if n1 < EPSILON || n2 < EPSILON {
+            return (coordinate::Coordinate::zero(), coordinate::Coordinate::zero());
+        }
+
+        let cos_angle = (v1.inner_product(v2) / (n1 * n2)).max(-1.).min(1.);
+        let angle_diff = cos_angle.acos() - self.rest_angle;
+        if angle_diff.abs() < EPSILON {
+            return (coordinate::Coordinate::zero(), coordinate::Coordinate::zero());
+        }
+
+        let plane_normal = v1.cross_product(v2);
+        let plane_normal_norm = plane_normal.norm();
+        if plane_normal_norm < EPSILON {
+            // a, b, c are collinear; bend direction is undefined
+            return (coordinate::Coordinate::zero(), coordinate::Coordinate::zero());
+        }
+
+        // within the a-b-c plane, perpendicular to each arm; moving a and c along
+        // these directions changes the angle at b
+        let dir_a = plane_normal.cross_product(v1) / (plane_normal_norm * n1);
+        let dir_c = v2.cross_product(plane_normal) / (plane_normal_norm * n2);
+
+        let magnitude = self.spring_constant * angle_diff;
+        // dir_a/dir_c close the angle, so a positive angle_diff (too open) pulls along them
+        (dir_a * magnitude, dir_c * magnitude)
+    }
+}
+
+/// GoalSpring pulls a somite toward an externally supplied goal position, with the
+/// restoring factor clamped to `[min_goal, max_goal]` so distant somites aren't
+/// yanked with unbounded force.
+pub struct GoalSpring {
+    spring_constant: f64,
+    min_goal: f64,
+    max_goal: f64,
+}
+
+impl GoalSpring {
+    pub fn new(spring_constant: f64, min_goal: f64, max_goal: f64) -> Self {
+        GoalSpring {
+            spring_constant: spring_constant,
+            min_goal: min_goal,
+            max_goal: max_goal,
+        }
+    }
+
+    pub fn force(
+        &self,
+        position: coordinate::Coordinate,
+        goal: coordinate::Coordinate,
+    ) -> coordinate::Coordinate {
+        let diff = goal - position;
+        let dist = diff.norm();
+        if dist < EPSILON {
+            return coordinate::Coordinate::zero();
+        }
+        let clamped_dist = dist.max(self.min_goal).min(self.max_goal);
+        let n = diff / dist;
+        n * self.spring_constant * clamped_dist
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bend_spring_straightens_bent_chain() {
+        let bend = BendSpring::new(1., f64::consts::PI);
+        let a = coordinate::Coordinate {
+            x: -1.,
+            y: 0.,
+            z: 0.,
+        };
+        let b = coordinate::Coordinate {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        let c = coordinate::Coordinate {
+            x: 1.,
+            y: 1.,
+            z: 0.,
+        };
+        let (force_a, force_c) = bend.force(a, b, c);
+        assert!(force_a.norm() > 0.);
+        assert!(force_c.norm() > 0.);
+        // c is bent up (+y) from the straight line; straightening pulls both ends down
+        assert!(force_a.y < 0.);
+        assert!(force_c.y < 0.);
+    }
+
+    #[test]
+    fn test_bend_spring_at_rest_angle_is_zero() {
+        let bend = BendSpring::new(1., f64::consts::PI);
+        let a = coordinate::Coordinate {
+            x: -1.,
+            y: 0.,
+            z: 0.,
+        };
+        let b = coordinate::Coordinate {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        let c = coordinate::Coordinate {
+            x: 1.,
+            y: 0.,
+            z: 0.,
+        };
+        let (force_a, force_c) = bend.force(a, b, c);
+        assert_eq!(force_a.norm(), 0.);
+        assert_eq!(force_c.norm(), 0.);
+    }
+
+    #[test]
+    fn test_goal_spring_clamps_distant_goal() {
+        let goal_spring = GoalSpring::new(10., 0., 1.);
+        let position = coordinate::Coordinate::zero();
+        let near_goal = coordinate::Coordinate {
+            x: 0.5,
+            y: 0.,
+            z: 0.,
+        };
+        let far_goal = coordinate::Coordinate {
+            x: 100.,
+            y: 0.,
+            z: 0.,
+        };
+        let near_force = goal_spring.force(position, near_goal);
+        let far_force = goal_spring.force(position, far_goal);
+        // both should be clamped to at most max_goal * spring_constant
+        assert!(near_force.norm() <= 10. + EPSILON);
+        assert_eq!(far_force.norm(), 10.);
+    }
+}
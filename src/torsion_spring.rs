@@ -1,6 +1,7 @@
 use std::fmt;
 use std::f64;
 use coordinate;
+use wrench;
 
 #[derive(Copy, Clone)]
 pub struct TorsionSpring {
@@ -36,6 +37,24 @@ impl TorsionSpring {
             * (self.angle(vec_bc, vec_ct) - target_angle)
     }
 
+    /// Same as `force`, but returns the full wrench (force + reaction torque about
+    /// `center`) applied at the tip, along with the equal-and-opposite wrench applied
+    /// at the base, so rotational effects aren't lost.
+    pub fn force_as_wrench(
+        &self,
+        base: coordinate::Coordinate,
+        center: coordinate::Coordinate,
+        tip: coordinate::Coordinate,
+        target_angle: f64,
+    ) -> (wrench::Wrench, wrench::Wrench) {
+        let applied_force = self.force(base, center, tip, target_angle);
+        let torque = (tip - center).cross_product(applied_force);
+        (
+            wrench::Wrench::new(applied_force, torque),
+            wrench::Wrench::new(applied_force * -1., torque * -1.),
+        )
+    }
+
     fn normal_vector(&self, v: coordinate::Coordinate) -> coordinate::Coordinate {
         // anti-clock-wise orthogonal vector to v, whose norm is 1
         self.standard_vector.cross_product(v) / v.norm()
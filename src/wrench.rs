@@ -0,0 +1,65 @@
+use std::ops;
+use coordinate::Coordinate;
+
+/// A force and the torque it produces, bundled so rotational effects aren't
+/// silently dropped when a force routine is applied away from a somite's center.
+#[derive(Copy, Clone)]
+pub struct Wrench {
+    pub force: Coordinate,
+    pub torque: Coordinate,
+}
+
+impl Wrench {
+    pub fn zero() -> Self {
+        Wrench {
+            force: Coordinate::zero(),
+            torque: Coordinate::zero(),
+        }
+    }
+
+    pub fn new(force: Coordinate, torque: Coordinate) -> Self {
+        Wrench {
+            force: force,
+            torque: torque,
+        }
+    }
+}
+
+impl ops::Add for Wrench {
+    type Output = Wrench;
+    fn add(self, rhs: Self) -> Self {
+        Wrench {
+            force: self.force + rhs.force,
+            torque: self.torque + rhs.torque,
+        }
+    }
+}
+
+impl ops::AddAssign for Wrench {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = Wrench {
+            force: self.force + rhs.force,
+            torque: self.torque + rhs.torque,
+        };
+    }
+}
+
+impl ops::Sub for Wrench {
+    type Output = Wrench;
+    fn sub(self, rhs: Self) -> Self {
+        Wrench {
+            force: self.force - rhs.force,
+            torque: self.torque - rhs.torque,
+        }
+    }
+}
+
+impl ops::Neg for Wrench {
+    type Output = Wrench;
+    fn neg(self) -> Self {
+        Wrench {
+            force: self.force * -1.,
+            torque: self.torque * -1.,
+        }
+    }
+}